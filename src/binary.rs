@@ -0,0 +1,131 @@
+//!
+//! Saving and loading an `Itemizer` in a compact binary format.
+//!
+//! The format is the magic bytes `ITMZ`, a version byte, and the bincode
+//! encoding of the values in id order as serialized by the `serde` feature.
+//!
+
+use crate::error::ItemizerError;
+use crate::item::ItemId;
+use crate::itemizer::Itemizer;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::hash::Hash;
+use std::io::{self, Read, Write};
+
+const MAGIC: &[u8; 4] = b"ITMZ";
+const VERSION: u8 = 1;
+
+impl<T, I, S> Itemizer<T, I, S>
+where
+    T: Serialize,
+    I: ItemId,
+{
+    /// Writes the `Itemizer` in the binary format read by `load_bin`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    ///
+    /// let mut buffer = vec![];
+    /// itemizer.save_bin(&mut buffer).unwrap();
+    ///
+    /// assert_eq!(&buffer[..4], b"ITMZ");
+    /// ```
+    ///
+    pub fn save_bin<W: Write>(&self, w: &mut W) -> Result<(), ItemizerError> {
+        w.write_all(MAGIC)?;
+        w.write_all(&[VERSION])?;
+        bincode::serialize_into(w, self)?;
+        Ok(())
+    }
+}
+
+impl<T> Itemizer<T>
+where
+    T: DeserializeOwned + Eq + Hash + Clone,
+{
+    /// Reads an `Itemizer` in the binary format written by `save_bin`.
+    ///
+    /// Returns `ItemizerError::UnsupportedFormat` if the input does not start
+    /// with the expected header, e.g. because it was written by another
+    /// version of the format.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// let mut buffer = vec![];
+    /// itemizer.save_bin(&mut buffer).unwrap();
+    ///
+    /// let loaded: Itemizer<String> = Itemizer::load_bin(buffer.as_slice()).unwrap();
+    /// assert_eq!(loaded.id_of_opt("item1"), Some(item1));
+    /// ```
+    ///
+    pub fn load_bin<R: Read>(mut r: R) -> Result<Itemizer<T>, ItemizerError> {
+        let mut header = [0; 5];
+        match r.read_exact(&mut header) {
+            Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => {
+                return Err(ItemizerError::UnsupportedFormat)
+            }
+            result => result?,
+        }
+        if header[..4] != MAGIC[..] || header[4] != VERSION {
+            return Err(ItemizerError::UnsupportedFormat);
+        }
+
+        Ok(bincode::deserialize_from(r)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bin_round_trip() {
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        itemizer.ids_of(&["milk", "bread", "eggs"].map(String::from));
+        itemizer.remove(&"bread".to_string());
+
+        let mut buffer = vec![];
+        itemizer.save_bin(&mut buffer).unwrap();
+        let mut loaded: Itemizer<String> = Itemizer::load_bin(buffer.as_slice()).unwrap();
+
+        assert_eq!(loaded, itemizer);
+        assert_eq!(loaded.id_of(&"jam".to_string()).as_index(), 1);
+    }
+
+    #[test]
+    fn test_load_bin_rejects_other_formats() {
+        let itemizer: Itemizer<String> = Itemizer::new();
+        let mut buffer = vec![];
+        itemizer.save_bin(&mut buffer).unwrap();
+
+        buffer[4] = VERSION + 1;
+        assert!(matches!(
+            Itemizer::<String>::load_bin(buffer.as_slice()),
+            Err(ItemizerError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            Itemizer::<String>::load_bin(&b"ITM"[..]),
+            Err(ItemizerError::UnsupportedFormat)
+        ));
+        assert!(matches!(
+            Itemizer::<String>::load_bin(&b"ITMZ\x01\xff"[..]),
+            Err(ItemizerError::Io(_))
+        ));
+        assert!(matches!(
+            Itemizer::<String>::load_bin(&b"ITMZ\x01\x01\0\0\0\0\0\0\0\x02"[..]),
+            Err(ItemizerError::Bincode(_))
+        ));
+    }
+}
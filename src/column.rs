@@ -0,0 +1,116 @@
+//!
+//! Interning the values of a column of a CSV file.
+//!
+
+use crate::error::ItemizerError;
+use crate::item::{Item, ItemId};
+use crate::itemizer::Itemizer;
+use csv::ReaderBuilder;
+use std::hash::BuildHasher;
+use std::io::Read;
+
+impl<I, S> Itemizer<String, I, S>
+where
+    I: ItemId,
+    S: BuildHasher,
+{
+    /// Interns the cells of the given column of a CSV file, counted from 0,
+    /// and returns their `Item`s in row order. If `has_headers` is `true`,
+    /// the first row is skipped.
+    ///
+    /// Rows may have different lengths, but a row without the column fails
+    /// with `ItemizerError::MalformedLine`. Items interned from earlier rows
+    /// are kept in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let csv = "id,product\n1,milk\n2,bread\n3,milk\n";
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let items = itemizer.intern_csv_column(csv.as_bytes(), 1, true).unwrap();
+    ///
+    /// assert_eq!(items, itemizer.ids_of(&["milk", "bread", "milk"].map(String::from)));
+    /// ```
+    ///
+    pub fn intern_csv_column<R: Read>(
+        &mut self,
+        reader: R,
+        column: usize,
+        has_headers: bool,
+    ) -> Result<Vec<Item<I>>, ItemizerError> {
+        let mut reader = ReaderBuilder::new()
+            .has_headers(has_headers)
+            .flexible(true)
+            .from_reader(reader);
+
+        let mut items = vec![];
+        for record in reader.records() {
+            let record = record?;
+            let cell = record.get(column).ok_or(ItemizerError::MalformedLine {
+                line: record
+                    .position()
+                    .map_or(0, |position| position.line() as usize),
+            })?;
+            items.push(self.try_id_of_str(cell)?);
+        }
+        Ok(items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_intern_csv_column() {
+        let csv = "basket,product,amount\n1,milk,2\n1,bread,1\n2,\"jam, sour\",1\n2,milk,3\n";
+
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        let items = itemizer.intern_csv_column(csv.as_bytes(), 1, true).unwrap();
+        assert_eq!(
+            items.iter().map(|item| item.as_index()).collect::<Vec<_>>(),
+            vec![0, 1, 2, 0]
+        );
+        assert_eq!(itemizer.value_of(&items[2]), "jam, sour");
+
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        let items = itemizer
+            .intern_csv_column(csv.as_bytes(), 0, false)
+            .unwrap();
+        assert_eq!(items.len(), 5);
+        assert_eq!(itemizer.value_of(&items[0]), "basket");
+        assert_eq!(itemizer.len(), 3);
+    }
+
+    #[test]
+    fn test_intern_csv_column_ragged() {
+        let csv = "basket,product\n1,milk\n2\n3,bread\n";
+
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        assert!(matches!(
+            itemizer.intern_csv_column(csv.as_bytes(), 1, true),
+            Err(ItemizerError::MalformedLine { line: 3 })
+        ));
+        assert!(itemizer.contains("milk"));
+        assert!(!itemizer.contains("bread"));
+    }
+
+    #[test]
+    fn test_intern_csv_column_matches_ids_of() {
+        let csv = "Milk\nbread\nmilk\nBREAD\njam\n";
+        let values = ["Milk", "bread", "milk", "BREAD", "jam"].map(String::from);
+
+        let mut itemizer = Itemizer::new_case_insensitive();
+        let mut expected = Itemizer::new_case_insensitive();
+        let items = itemizer
+            .intern_csv_column(csv.as_bytes(), 0, false)
+            .unwrap();
+
+        assert_eq!(items, expected.ids_of(&values));
+        assert_eq!(itemizer, expected);
+        assert_eq!(itemizer.stats(), expected.stats());
+    }
+}
@@ -0,0 +1,289 @@
+//!
+//! An itemizer that can assign ids from many threads at once.
+//!
+//! `ConcurrentItemizer` splits its items into shards, each behind its own
+//! lock, so threads interning different items rarely wait on each other.
+//! Every distinct item gets exactly one id, even when several threads intern
+//! it at the same time, but ids are not assigned in any particular order.
+//!
+//! # Examples
+//!
+//! ```
+//! use itemizer::ConcurrentItemizer;
+//! use std::thread;
+//!
+//! let itemizer = ConcurrentItemizer::new();
+//! thread::scope(|s| {
+//!     s.spawn(|| itemizer.id_of(&"item1".to_string()));
+//!     s.spawn(|| itemizer.id_of(&"item1".to_string()));
+//! });
+//!
+//! assert_eq!(itemizer.len(), 1);
+//! ```
+//!
+
+use crate::error::ItemizerError;
+use crate::item::Item;
+use crate::itemizer::Itemizer;
+use fnv::{FnvBuildHasher, FnvHashMap};
+use std::hash::{BuildHasher, Hash};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{PoisonError, RwLock};
+
+const DEFAULT_SHARDS: usize = 16;
+
+/// Maps items of type `T` to `Item`s from many threads at once.
+/// `into_itemizer` turns it into an `Itemizer` once all items are known.
+pub struct ConcurrentItemizer<T> {
+    next_item_id: AtomicU32,
+    shards: Vec<RwLock<FnvHashMap<T, Item>>>,
+    hasher: FnvBuildHasher,
+}
+
+impl<T> ConcurrentItemizer<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// Creates a new, empty `ConcurrentItemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ConcurrentItemizer;
+    ///
+    /// let itemizer: ConcurrentItemizer<String> = ConcurrentItemizer::new();
+    /// assert!(itemizer.is_empty());
+    /// ```
+    ///
+    pub fn new() -> ConcurrentItemizer<T> {
+        ConcurrentItemizer::with_shards(DEFAULT_SHARDS)
+    }
+
+    /// Creates a new, empty `ConcurrentItemizer` with the given number of
+    /// shards. More shards mean less contention between threads.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `shards` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ConcurrentItemizer;
+    ///
+    /// let itemizer: ConcurrentItemizer<String> = ConcurrentItemizer::with_shards(64);
+    /// assert!(itemizer.is_empty());
+    /// ```
+    ///
+    pub fn with_shards(shards: usize) -> ConcurrentItemizer<T> {
+        assert!(shards > 0, "a concurrent itemizer needs at least one shard");
+        ConcurrentItemizer {
+            next_item_id: AtomicU32::new(0),
+            shards: (0..shards).map(|_| RwLock::default()).collect(),
+            hasher: FnvBuildHasher::default(),
+        }
+    }
+
+    /// Returns the `Item` for the given item. If the item is not in the
+    /// `ConcurrentItemizer`, it is added and a new `Item` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all `u32` ids have been assigned. Use `try_id_of` to handle
+    /// this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ConcurrentItemizer;
+    ///
+    /// let itemizer = ConcurrentItemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// assert_eq!(itemizer.id_of(&"item1".to_string()), item1);
+    /// ```
+    ///
+    pub fn id_of(&self, item: &T) -> Item {
+        match self.try_id_of(item) {
+            Ok(id) => id,
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Returns the `Item` for the given item like `id_of`, but fails instead
+    /// of panicking if no more ids are available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ConcurrentItemizer;
+    ///
+    /// let itemizer = ConcurrentItemizer::new();
+    ///
+    /// assert_eq!(itemizer.try_id_of(&"item1".to_string()).unwrap().as_index(), 0);
+    /// ```
+    ///
+    pub fn try_id_of(&self, item: &T) -> Result<Item, ItemizerError> {
+        if let Some(id) = self.id_of_opt(item) {
+            return Ok(id);
+        }
+
+        let mut shard = self
+            .shard(item)
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        // Another thread may have added the item since we looked it up.
+        if let Some(id) = shard.get(item) {
+            return Ok(*id);
+        }
+
+        let id = self
+            .next_item_id
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |id| id.checked_add(1))
+            .map_err(|_| ItemizerError::IdSpaceExhausted)?;
+        shard.insert(item.clone(), Item::with_id(id));
+
+        Ok(Item::with_id(id))
+    }
+
+    /// Returns the `Item` for the given item if it exists in the
+    /// `ConcurrentItemizer`. If the item is not present, `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ConcurrentItemizer;
+    ///
+    /// let itemizer = ConcurrentItemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// assert_eq!(itemizer.id_of_opt(&"item1".to_string()), Some(item1));
+    /// assert_eq!(itemizer.id_of_opt(&"item2".to_string()), None);
+    /// ```
+    ///
+    pub fn id_of_opt(&self, item: &T) -> Option<Item> {
+        self.shard(item)
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(item)
+            .copied()
+    }
+
+    /// Returns the number of items in the `ConcurrentItemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ConcurrentItemizer;
+    ///
+    /// let itemizer = ConcurrentItemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// itemizer.id_of(&"item1".to_string());
+    ///
+    /// assert_eq!(itemizer.len(), 1);
+    /// ```
+    ///
+    pub fn len(&self) -> usize {
+        self.next_item_id.load(Ordering::Relaxed) as usize
+    }
+
+    /// Returns `true` if the `ConcurrentItemizer` contains no items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ConcurrentItemizer;
+    ///
+    /// let itemizer = ConcurrentItemizer::new();
+    /// assert!(itemizer.is_empty());
+    /// itemizer.id_of(&"item1".to_string());
+    /// assert!(!itemizer.is_empty());
+    /// ```
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Turns the `ConcurrentItemizer` into an `Itemizer`, keeping all id
+    /// assignments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ConcurrentItemizer;
+    ///
+    /// let concurrent = ConcurrentItemizer::new();
+    /// let item1 = concurrent.id_of(&"item1".to_string());
+    ///
+    /// let itemizer = concurrent.into_itemizer();
+    /// assert_eq!(itemizer.value_of(&item1), "item1");
+    /// ```
+    ///
+    pub fn into_itemizer(self) -> Itemizer<T> {
+        let mut pairs: Vec<(Item, T)> = self
+            .shards
+            .into_iter()
+            .flat_map(|shard| shard.into_inner().unwrap_or_else(PoisonError::into_inner))
+            .map(|(value, id)| (id, value))
+            .collect();
+        pairs.sort_unstable_by_key(|(id, _)| *id);
+
+        Itemizer::from_values(pairs.into_iter().map(|(_, value)| value).collect())
+    }
+
+    fn shard(&self, item: &T) -> &RwLock<FnvHashMap<T, Item>> {
+        let hash = self.hasher.hash_one(item) as usize;
+        &self.shards[hash % self.shards.len()]
+    }
+}
+
+impl<T> Default for ConcurrentItemizer<T>
+where
+    T: Eq + Hash + Clone,
+{
+    fn default() -> Self {
+        ConcurrentItemizer::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn test_concurrent_id_of() {
+        let itemizer = ConcurrentItemizer::with_shards(4);
+
+        let results: Vec<Vec<(u32, Item)>> = thread::scope(|s| {
+            let handles: Vec<_> = (0..8)
+                .map(|thread| {
+                    let itemizer = &itemizer;
+                    s.spawn(move || {
+                        (0..500)
+                            .map(|i| {
+                                let value = (i * (thread + 1)) % 1000;
+                                (value, itemizer.id_of(&value))
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+
+        let distinct: std::collections::HashSet<u32> =
+            results.iter().flatten().map(|(value, _)| *value).collect();
+        assert_eq!(itemizer.len(), distinct.len());
+
+        for (value, id) in results.iter().flatten() {
+            assert_eq!(itemizer.id_of_opt(value), Some(*id));
+        }
+
+        let itemizer = itemizer.into_itemizer();
+        assert_eq!(itemizer.len(), distinct.len());
+        for (value, id) in results.iter().flatten() {
+            assert_eq!(itemizer.value_of(id), value);
+        }
+    }
+}
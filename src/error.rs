@@ -0,0 +1,139 @@
+//!
+//! Errors returned by the fallible operations of an `Itemizer`.
+//!
+
+use alloc::string::String;
+use core::error::Error;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+/// The error type of the fallible operations of an `Itemizer` and the other
+/// itemizers of this crate.
+///
+/// Each variant names the id or line that caused it, where there is one.
+#[derive(Debug)]
+pub enum ItemizerError {
+    /// All ids of the id type have been assigned, so no new item can be
+    /// added.
+    IdSpaceExhausted,
+    /// Reading or writing failed.
+    #[cfg(feature = "std")]
+    Io(io::Error),
+    /// A line could not be parsed. Lines are numbered from 1.
+    MalformedLine { line: usize },
+    /// Ids are not dense and ascending from 0: `found` was given where
+    /// `expected` was the next id.
+    UnexpectedId { expected: usize, found: usize },
+    /// The value with the given id has already been assigned another id.
+    DuplicateValue { id: usize },
+    /// The id is already assigned to another value.
+    IdOccupied { id: usize },
+    /// The index does not fit into the item id type.
+    IdOutOfRange { index: usize },
+    /// The itemizer already holds as many distinct items as it may.
+    CapacityExceeded,
+    /// The id is not assigned to any item, e.g. because it has been freed by
+    /// `remove`, so the ids are not dense.
+    NotDense { id: usize },
+    /// The input is not in the expected format, or in an unsupported version
+    /// of it.
+    UnsupportedFormat,
+    /// The map from values to ids holds `ids` entries, but there are `values`
+    /// live values.
+    LengthMismatch { values: usize, ids: usize },
+    /// The value with the given id is not in the map from values to ids.
+    UnmappedValue { id: usize },
+    /// The itemizer has a normalizer, which the requested form cannot hold.
+    Normalized,
+    /// The item, given in its `Debug` form, is not in the itemizer.
+    UnknownItem(String),
+    /// Binary encoding or decoding failed.
+    #[cfg(feature = "bincode")]
+    Bincode(bincode::Error),
+    /// Parsing CSV failed.
+    #[cfg(feature = "csv")]
+    Csv(csv::Error),
+}
+
+impl fmt::Display for ItemizerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ItemizerError::IdSpaceExhausted => write!(f, "no more item ids available"),
+            #[cfg(feature = "std")]
+            ItemizerError::Io(err) => write!(f, "i/o error: {}", err),
+            ItemizerError::MalformedLine { line } => write!(f, "malformed line {}", line),
+            ItemizerError::UnexpectedId { expected, found } => {
+                write!(f, "expected item id {}, found {}", expected, found)
+            }
+            ItemizerError::DuplicateValue { id } => {
+                write!(f, "value of item id {} is a duplicate", id)
+            }
+            ItemizerError::IdOccupied { id } => {
+                write!(f, "item id {} is already assigned", id)
+            }
+            ItemizerError::IdOutOfRange { index } => {
+                write!(f, "index {} does not fit into the item id type", index)
+            }
+            ItemizerError::CapacityExceeded => write!(f, "maximum number of items reached"),
+            ItemizerError::NotDense { id } => write!(f, "item id {} is free", id),
+            ItemizerError::UnsupportedFormat => write!(f, "unsupported format"),
+            ItemizerError::LengthMismatch { values, ids } => {
+                write!(f, "{} values but {} ids", values, ids)
+            }
+            ItemizerError::UnmappedValue { id } => {
+                write!(f, "value of item id {} has no id", id)
+            }
+            ItemizerError::Normalized => write!(f, "itemizer has a normalizer"),
+            ItemizerError::UnknownItem(item) => write!(f, "unknown item {}", item),
+            #[cfg(feature = "bincode")]
+            ItemizerError::Bincode(err) => write!(f, "bincode error: {}", err),
+            #[cfg(feature = "csv")]
+            ItemizerError::Csv(err) => write!(f, "csv error: {}", err),
+        }
+    }
+}
+
+impl Error for ItemizerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            ItemizerError::Io(err) => Some(err),
+            #[cfg(feature = "bincode")]
+            ItemizerError::Bincode(err) => Some(err),
+            #[cfg(feature = "csv")]
+            ItemizerError::Csv(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for ItemizerError {
+    fn from(err: io::Error) -> ItemizerError {
+        ItemizerError::Io(err)
+    }
+}
+
+#[cfg(feature = "bincode")]
+impl From<bincode::Error> for ItemizerError {
+    fn from(err: bincode::Error) -> ItemizerError {
+        match *err {
+            bincode::ErrorKind::Io(err) => ItemizerError::Io(err),
+            err => ItemizerError::Bincode(Box::new(err)),
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for ItemizerError {
+    fn from(err: csv::Error) -> ItemizerError {
+        if !err.is_io_error() {
+            return ItemizerError::Csv(err);
+        }
+        match err.into_kind() {
+            csv::ErrorKind::Io(err) => ItemizerError::Io(err),
+            _ => unreachable!("csv reported an i/o error of another kind"),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//!
+//! A read-only form of an `Itemizer`.
+//!
+//! Once all items have been added, an `Itemizer` can be frozen into a
+//! `FrozenItemizer`. It cannot assign ids to new items, but it is `Sync`
+//! whenever `T` is, so it can be wrapped in an `Arc` and queried from many
+//! threads without locking.
+//!
+//! # Examples
+//!
+//! ```
+//! use itemizer::Itemizer;
+//! use std::sync::Arc;
+//! use std::thread;
+//!
+//! let mut itemizer = Itemizer::new();
+//! let item1 = itemizer.id_of(&"item1".to_string());
+//!
+//! let frozen = Arc::new(itemizer.freeze());
+//! let shared = Arc::clone(&frozen);
+//! let handle = thread::spawn(move || shared.id_of_opt(&"item1".to_string()));
+//!
+//! assert_eq!(handle.join().unwrap(), Some(item1));
+//! ```
+//!
+
+use crate::item::{Item, ItemId};
+use crate::itemizer::{Itemizer, Normalizer};
+use crate::iter::Slots;
+use crate::HashMap;
+use alloc::borrow::{Borrow, ToOwned};
+use alloc::vec::Vec;
+use core::hash::{BuildHasher, Hash};
+use fnv::FnvBuildHasher;
+
+/// A read-only `Itemizer`, created by `Itemizer::freeze` and turned back into
+/// one by `thaw`.
+pub struct FrozenItemizer<T, I = u32, S = FnvBuildHasher> {
+    item_str_to_id: HashMap<T, Item<I>, S>,
+    item_id_to_str: Vec<Option<T>>,
+    free_ids: Vec<I>,
+    normalizer: Option<Normalizer<T>>,
+}
+
+impl<T, I, S> FrozenItemizer<T, I, S>
+where
+    T: Eq + Hash + Clone,
+    I: ItemId,
+    S: BuildHasher,
+{
+    pub(crate) fn new(
+        item_str_to_id: HashMap<T, Item<I>, S>,
+        item_id_to_str: Vec<Option<T>>,
+        free_ids: Vec<I>,
+        normalizer: Option<Normalizer<T>>,
+    ) -> FrozenItemizer<T, I, S> {
+        FrozenItemizer {
+            item_str_to_id,
+            item_id_to_str,
+            free_ids,
+            normalizer,
+        }
+    }
+
+    /// Returns the `Item` for the given item if it exists in the
+    /// `FrozenItemizer`. If the item is not present, `None` is returned.
+    ///
+    /// Like `Itemizer::id_of_opt`, this accepts any borrowed form of `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// let frozen = itemizer.freeze();
+    ///
+    /// assert_eq!(frozen.id_of_opt(&"item1".to_string()), Some(item1));
+    /// assert_eq!(frozen.id_of_opt(&"item2".to_string()), None);
+    /// ```
+    ///
+    pub fn id_of_opt<Q>(&self, item: &Q) -> Option<Item<I>>
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = T> + ?Sized,
+    {
+        match &self.normalizer {
+            Some(normalizer) => self
+                .item_str_to_id
+                .get(normalizer(&item.to_owned()).borrow())
+                .copied(),
+            None => self.item_str_to_id.get(item).copied(),
+        }
+    }
+
+    /// Returns the value of the given `Item`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Item` is out of range or its id has been freed by
+    /// `Itemizer::remove` before freezing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// let frozen = itemizer.freeze();
+    ///
+    /// assert_eq!(frozen.value_of(&item1), &"item1".to_string());
+    /// ```
+    ///
+    pub fn value_of(&self, id: &Item<I>) -> &T {
+        match &self.item_id_to_str[id.as_index()] {
+            Some(value) => value,
+            None => panic!(
+                "item id {} has been removed from the itemizer",
+                id.as_index()
+            ),
+        }
+    }
+
+    /// Returns the number of items in the `FrozenItemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// let frozen = itemizer.freeze();
+    ///
+    /// assert_eq!(frozen.len(), 1);
+    /// ```
+    ///
+    pub fn len(&self) -> usize {
+        self.item_id_to_str.len()
+    }
+
+    /// Returns `true` if the `FrozenItemizer` contains no items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let itemizer: Itemizer<String> = Itemizer::new();
+    /// assert!(itemizer.freeze().is_empty());
+    /// ```
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.item_id_to_str.is_empty()
+    }
+
+    /// Returns an iterator over the items in the `FrozenItemizer` in id
+    /// order, skipping ids freed by `Itemizer::remove`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// let frozen = itemizer.freeze();
+    ///
+    /// let mut iter = frozen.iter();
+    /// assert_eq!(iter.next(), Some(&"item1".to_string()));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    ///
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        Slots::new(&self.item_id_to_str, self.item_str_to_id.len()).map(|(_, value)| value)
+    }
+
+    /// Turns the `FrozenItemizer` back into a mutable `Itemizer`, keeping all
+    /// id assignments.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    ///
+    /// let mut itemizer = itemizer.freeze().thaw();
+    /// assert_eq!(itemizer.id_of(&"item2".to_string()).as_index(), 1);
+    /// ```
+    ///
+    pub fn thaw(self) -> Itemizer<T, I, S> {
+        Itemizer::from_raw_parts(
+            self.item_str_to_id,
+            self.item_id_to_str,
+            self.free_ids,
+            self.normalizer,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::{String, ToString};
+    use alloc::vec;
+
+    fn assert_send_sync<S: Send + Sync>() {}
+
+    #[test]
+    fn test_frozen_is_sync() {
+        assert_send_sync::<FrozenItemizer<String>>();
+    }
+
+    #[test]
+    fn test_freeze() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        let frozen = itemizer.freeze();
+        assert_eq!(frozen.len(), 2);
+        assert_eq!(frozen.id_of_opt(&"item1".to_string()), Some(item1));
+        assert_eq!(frozen.id_of_opt(&"item2".to_string()), Some(item2));
+        assert_eq!(frozen.id_of_opt(&"item3".to_string()), None);
+        assert_eq!(frozen.value_of(&item2), &"item2".to_string());
+        assert_eq!(
+            frozen.iter().collect::<Vec<_>>(),
+            vec![&"item1".to_string(), &"item2".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_thaw() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+        itemizer.remove(&"item1".to_string());
+
+        let mut itemizer = itemizer.freeze().thaw();
+        assert_eq!(itemizer.id_of_opt(&"item2".to_string()), Some(item2));
+        assert_eq!(itemizer.id_of(&"item3".to_string()), item1);
+        assert_eq!(itemizer.id_of(&"item4".to_string()).as_index(), 2);
+    }
+}
@@ -0,0 +1,62 @@
+//!
+//! Estimating the heap memory held by values.
+//!
+
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::mem::size_of;
+
+/// Types that can estimate the heap memory they own, not counting their own
+/// size. Used by `Itemizer::estimated_heap_bytes_deep`.
+///
+/// # Examples
+///
+/// ```
+/// use itemizer::HeapSize;
+///
+/// assert_eq!(42u32.heap_size(), 0);
+/// assert!(String::from("milk").heap_size() >= 4);
+/// ```
+///
+pub trait HeapSize {
+    /// Returns the number of heap bytes owned by the value.
+    fn heap_size(&self) -> usize;
+}
+
+macro_rules! impl_heap_size_zero {
+    ($($ty:ty),*) => {
+        $(impl HeapSize for $ty {
+            fn heap_size(&self) -> usize {
+                0
+            }
+        })*
+    };
+}
+
+impl_heap_size_zero!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, bool, char);
+
+impl<T: ?Sized> HeapSize for &T {
+    /// Borrowed values are owned elsewhere.
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl HeapSize for Box<str> {
+    fn heap_size(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(HeapSize::heap_size).sum::<usize>()
+    }
+}
@@ -21,6 +21,8 @@
 //! It is used to represent an item in a transaction.
 //!
 //! The Item is a simple wrapper around a `u32` that represents the unique ID of the item.
+//! Other id widths can be chosen through the `ItemId` type parameter, e.g. `Item<u16>`
+//! halves the size of encoded transactions for small vocabularies.
 //!
 //! # Examples
 //!
@@ -35,13 +37,109 @@
 //! ```
 //!
 
+use crate::error::ItemizerError;
+use alloc::vec::Vec;
+use core::fmt::{self, Debug};
+use core::hash::Hash;
+use core::iter::Map;
+use core::ops::Range;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// An unsigned integer type that can be used as the id of an `Item`.
+///
+/// This trait is sealed and implemented for `u16`, `u32` and `u64`.
+pub trait ItemId: private::Sealed + Copy + Eq + Ord + Hash + Debug {
+    /// Converts an index into an id, or returns `None` if it does not fit.
+    fn from_usize(index: usize) -> Option<Self>;
+
+    /// Converts the id into an index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the id does not fit into `usize`, e.g. a large `u64` id on
+    /// a 32-bit target.
+    fn to_usize(self) -> usize;
+}
+
+macro_rules! impl_item_id {
+    ($($id:ty),*) => {
+        $(
+            impl private::Sealed for $id {}
+
+            impl ItemId for $id {
+                fn from_usize(index: usize) -> Option<Self> {
+                    <$id>::try_from(index).ok()
+                }
+
+                fn to_usize(self) -> usize {
+                    match usize::try_from(self) {
+                        Ok(index) => index,
+                        Err(_) => panic!("item id {} does not fit into usize", self),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_item_id!(u16, u32, u64);
+
 #[derive(Copy, Clone, Hash, PartialOrd, PartialEq, Eq, Ord, Debug)]
-pub struct Item {
-    id: u32,
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
+#[repr(transparent)]
+pub struct Item<I = u32> {
+    id: I,
 }
 
-impl Item {
-    /// Creates a new `Item` with the given ID.
+impl<I: ItemId> Item<I> {
+    /// Creates a new `Item` for the given index, or returns `None` if the
+    /// index does not fit into the id type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Item;
+    ///
+    /// let item: Item<u16> = Item::from_index(7).unwrap();
+    ///
+    /// assert_eq!(item.as_index(), 7);
+    /// assert_eq!(Item::<u16>::from_index(70_000), None);
+    /// ```
+    ///
+    pub fn from_index(index: usize) -> Option<Item<I>> {
+        I::from_usize(index).map(|id| Item { id })
+    }
+
+    /// Creates a new `Item` for an index that is known to fit into the id
+    /// type, i.e. one that has been assigned before.
+    pub(crate) fn at_index(index: usize) -> Item<I> {
+        match I::from_usize(index) {
+            Some(id) => Item { id },
+            None => panic!("index {} does not fit into the item id type", index),
+        }
+    }
+
+    /// Returns the raw ID of the `Item`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Item;
+    ///
+    /// let item: Item<u16> = Item::from_index(7).unwrap();
+    ///
+    /// assert_eq!(item.id(), 7u16);
+    /// ```
+    ///
+    pub fn id(&self) -> I {
+        self.id
+    }
+
+    /// Returns the ID of the `Item` as a `usize`.
     ///
     /// # Examples
     ///
@@ -55,11 +153,13 @@ impl Item {
     /// assert_eq!(item2.as_index(), 1);
     /// ```
     ///
-    pub fn with_id(id: u32) -> Item {
-        Item { id: id }
+    pub fn as_index(&self) -> usize {
+        self.id.to_usize()
     }
+}
 
-    /// Returns the ID of the `Item` as a `usize`.
+impl Item {
+    /// Creates a new `Item` with the given ID.
     ///
     /// # Examples
     ///
@@ -73,7 +173,287 @@ impl Item {
     /// assert_eq!(item2.as_index(), 1);
     /// ```
     ///
-    pub fn as_index(&self) -> usize {
-        self.id as usize
+    pub fn with_id(id: u32) -> Item {
+        Item { id }
+    }
+
+    /// Returns the raw ID of the `Item` as a `u32`.
+    ///
+    /// Unlike `as_index`, the width of the returned value does not depend on
+    /// the platform.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Item;
+    ///
+    /// let item = Item::with_id(42);
+    ///
+    /// assert_eq!(item.as_u32(), 42);
+    /// assert_eq!(item.as_u32() as usize, item.as_index());
+    /// ```
+    ///
+    pub fn as_u32(&self) -> u32 {
+        self.id
+    }
+}
+
+/// A block of consecutive `Item`s, as returned by
+/// `Itemizer::intern_new_block`.
+///
+/// # Examples
+///
+/// ```
+/// use itemizer::{Item, Itemizer};
+///
+/// let mut itemizer = Itemizer::new();
+/// let range = itemizer.intern_new_block(&["a", "b"]);
+///
+/// assert_eq!(range.into_iter().collect::<Vec<_>>(), vec![Item::with_id(0), Item::with_id(1)]);
+/// ```
+///
+#[derive(Copy, Clone, Hash, PartialEq, Eq, Debug)]
+pub struct ItemRange<I = u32> {
+    start: Item<I>,
+    len: usize,
+}
+
+impl<I: ItemId> ItemRange<I> {
+    pub(crate) fn new(start: Item<I>, len: usize) -> ItemRange<I> {
+        ItemRange { start, len }
+    }
+
+    /// Returns the first `Item` of the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"a");
+    /// let range = itemizer.intern_new_block(&["b", "c"]);
+    ///
+    /// assert_eq!(range.start().as_index(), 1);
+    /// ```
+    ///
+    pub fn start(&self) -> Item<I> {
+        self.start
+    }
+
+    /// Returns the number of `Item`s in the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let range = itemizer.intern_new_block(&["a", "b"]);
+    ///
+    /// assert_eq!(range.len(), 2);
+    /// ```
+    ///
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the range contains no `Item`s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer: Itemizer<&str> = Itemizer::new();
+    /// assert!(itemizer.intern_new_block(&[]).is_empty());
+    /// ```
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the given `Item` is in the range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let range = itemizer.intern_new_block(&["a", "b"]);
+    ///
+    /// assert!(range.contains(&Item::with_id(1)));
+    /// assert!(!range.contains(&Item::with_id(2)));
+    /// ```
+    ///
+    pub fn contains(&self, item: &Item<I>) -> bool {
+        (self.start.as_index()..self.start.as_index() + self.len).contains(&item.as_index())
+    }
+}
+
+impl<I: ItemId> IntoIterator for ItemRange<I> {
+    type Item = Item<I>;
+    type IntoIter = Map<Range<usize>, fn(usize) -> Item<I>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let start = self.start.as_index();
+        (start..start + self.len).map(Item::at_index as fn(usize) -> Item<I>)
+    }
+}
+
+/// Rewrites encoded items through a remap table as returned by e.g.
+/// `Itemizer::retain` or `Itemizer::compact`, which maps every old id to its
+/// new `Item` or to `None`.
+///
+/// Items that map to `None`, or whose id is beyond the end of the table,
+/// are dropped.
+///
+/// # Examples
+///
+/// ```
+/// use itemizer::{apply_remap, Item};
+///
+/// let remap = vec![Some(Item::with_id(1)), None, Some(Item::with_id(0))];
+/// let items = vec![Item::with_id(0), Item::with_id(1), Item::with_id(2), Item::with_id(7)];
+///
+/// assert_eq!(apply_remap(&items, &remap), vec![Item::with_id(1), Item::with_id(0)]);
+/// ```
+///
+pub fn apply_remap<I: ItemId>(items: &[Item<I>], remap: &[Option<Item<I>>]) -> Vec<Item<I>> {
+    items
+        .iter()
+        .filter_map(|item| remap.get(item.as_index()).copied().flatten())
+        .collect()
+}
+
+/// Reinterprets a slice of `Item`s as the slice of their ids, without
+/// copying, e.g. to write an encoded transaction to disk as raw `u32`s.
+///
+/// # Examples
+///
+/// ```
+/// use itemizer::{items_as_u32, Item};
+///
+/// let items = vec![Item::with_id(3), Item::with_id(1)];
+///
+/// assert_eq!(items_as_u32(&items), &[3, 1]);
+/// assert!(items_as_u32(&items)
+///     .iter()
+///     .zip(&items)
+///     .all(|(id, item)| *id == item.as_u32()));
+/// ```
+///
+pub fn items_as_u32(items: &[Item]) -> &[u32] {
+    // SAFETY: `Item` is `repr(transparent)` over its id, so `Item<u32>` has
+    // the same size, alignment and validity as `u32`, and the returned slice
+    // borrows from `items`.
+    unsafe { core::slice::from_raw_parts(items.as_ptr().cast::<u32>(), items.len()) }
+}
+
+/// Adds `offset` to the id of every item, e.g. the `len()` of another
+/// itemizer whose id space this one is appended to.
+///
+/// # Panics
+///
+/// Panics if any id would overflow `u32`. The items are left unchanged in
+/// that case.
+///
+/// # Examples
+///
+/// ```
+/// use itemizer::{rebase, Item};
+///
+/// let mut items = vec![Item::with_id(0), Item::with_id(2)];
+/// rebase(&mut items, 10);
+///
+/// assert_eq!(items, vec![Item::with_id(10), Item::with_id(12)]);
+/// ```
+///
+/// ```should_panic
+/// use itemizer::{rebase, Item};
+///
+/// rebase(&mut [Item::with_id(u32::MAX)], 1);
+/// ```
+///
+pub fn rebase(items: &mut [Item], offset: u32) {
+    if let Some(max) = items.iter().map(|item| item.id).max() {
+        assert!(
+            max.checked_add(offset).is_some(),
+            "rebasing item id {} by {} overflows",
+            max,
+            offset
+        );
+    }
+    for item in items {
+        item.id += offset;
+    }
+}
+
+impl<I: ItemId> fmt::Display for Item<I> {
+    /// Writes the bare index of the `Item`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Item;
+    ///
+    /// assert_eq!(format!("{}", Item::with_id(3)), "3");
+    /// ```
+    ///
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_index())
+    }
+}
+
+impl<I: ItemId> From<Item<I>> for usize {
+    /// Returns the index of the `Item`, like `as_index`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Item;
+    ///
+    /// assert_eq!(usize::from(Item::with_id(3)), 3);
+    /// ```
+    ///
+    fn from(item: Item<I>) -> usize {
+        item.as_index()
+    }
+}
+
+impl From<Item> for u32 {
+    /// Returns the raw ID of the `Item`, like `as_u32`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Item;
+    ///
+    /// assert_eq!(u32::from(Item::with_id(3)), 3);
+    /// ```
+    ///
+    fn from(item: Item) -> u32 {
+        item.as_u32()
+    }
+}
+
+impl<I: ItemId> TryFrom<usize> for Item<I> {
+    type Error = ItemizerError;
+
+    /// Creates an `Item` for the given index like `from_index`, or returns
+    /// `ItemizerError::IdOutOfRange` if it does not fit into the id type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Item;
+    ///
+    /// assert_eq!(Item::try_from(3).unwrap(), Item::with_id(3));
+    /// assert!(Item::<u16>::try_from(70_000).is_err());
+    /// ```
+    ///
+    fn try_from(index: usize) -> Result<Item<I>, ItemizerError> {
+        Item::from_index(index).ok_or(ItemizerError::IdOutOfRange { index })
     }
 }
@@ -16,16 +16,120 @@
 // make the itemized type abstract
 // shifting the index to also use 0
 
-use crate::item::Item;
-use fnv::FnvHashMap;
-use std::fmt::Debug;
-use std::hash::Hash;
-use std::slice::Iter;
-
-pub struct Itemizer<T> {
-    next_item_id: u32,
-    item_str_to_id: FnvHashMap<T, Item>,
-    item_id_to_str: Vec<T>,
+use crate::error::ItemizerError;
+use crate::frozen::FrozenItemizer;
+use crate::heap::HeapSize;
+use crate::item::{Item, ItemId, ItemRange};
+use crate::iter::{IntoIter, Slots};
+use crate::sorted::SortedItemizer;
+use crate::{HashMap, HashSet};
+use alloc::borrow::{Borrow, Cow, ToOwned};
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Debug;
+use core::hash::{BuildHasher, Hash};
+use core::mem::{self, size_of};
+use core::ops::Index;
+use fnv::FnvBuildHasher;
+
+/// Maps an item to the key it is looked up by.
+pub(crate) type Normalizer<T> = Arc<dyn Fn(&T) -> T + Send + Sync>;
+
+/// The state of an `Itemizer` at some point, created by `checkpoint` and
+/// consumed by `rollback`.
+#[derive(Clone, Debug)]
+pub struct Checkpoint<I = u32> {
+    next_item_id: I,
+    free_ids: Vec<I>,
+}
+
+/// How often an `Itemizer` has been asked to intern an item, as returned by
+/// `stats`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ItemizerStats {
+    /// The number of items looked up to be interned.
+    pub lookups: u64,
+    /// The number of lookups that found the item already present.
+    pub hits: u64,
+    /// The number of items that have been added after a lookup missed.
+    pub inserts: u64,
+}
+
+/// Maps items of type `T` to `Item`s.
+///
+/// Ids are `u32` by default. Another `ItemId` type can be chosen through the
+/// second type parameter, in which case the `Itemizer` is created through
+/// `Default` or `FromIterator`.
+///
+/// Items are hashed with FNV by default, which is deterministic across runs,
+/// so an `Itemizer` fed the same items in the same order always assigns the
+/// same ids. Another hasher can be chosen through the third type parameter,
+/// see `with_hasher`.
+///
+/// Removing an item frees its id without renumbering the others. A freed id
+/// has no value until it is reused: every iterator skips it, `value_of_opt`
+/// returns `None` for it and `value_of` panics. It still counts towards
+/// `len`, which is the size of the id space.
+///
+/// # Examples
+///
+/// ```
+/// use itemizer::{Item, Itemizer};
+///
+/// let mut itemizer: Itemizer<String, u16> = Itemizer::default();
+/// let item1: Item<u16> = itemizer.id_of(&"item1".to_string());
+///
+/// assert_eq!(item1.as_index(), 0);
+/// ```
+///
+#[derive(Clone)]
+pub struct Itemizer<T, I = u32, S = FnvBuildHasher> {
+    next_item_id: I,
+    item_str_to_id: HashMap<T, Item<I>, S>,
+    item_id_to_str: Vec<Option<T>>,
+    free_ids: Vec<I>,
+    counts: Vec<u32>,
+    normalizer: Option<Normalizer<T>>,
+    max_items: Option<usize>,
+    stats: ItemizerStats,
+}
+
+impl<T, I, S> Itemizer<T, I, S>
+where
+    I: ItemId,
+{
+    pub(crate) fn from_raw_parts(
+        item_str_to_id: HashMap<T, Item<I>, S>,
+        item_id_to_str: Vec<Option<T>>,
+        free_ids: Vec<I>,
+        normalizer: Option<Normalizer<T>>,
+    ) -> Itemizer<T, I, S> {
+        Itemizer {
+            next_item_id: Item::<I>::at_index(item_id_to_str.len()).id(),
+            item_str_to_id,
+            item_id_to_str,
+            free_ids,
+            counts: vec![],
+            normalizer,
+            max_items: None,
+            stats: ItemizerStats::default(),
+        }
+    }
+
+    /// Returns the value of every id in id order, or `None` for ids freed by
+    /// `remove`.
+    pub(crate) fn slots(&self) -> impl Iterator<Item = Option<&T>> {
+        self.item_id_to_str.iter().map(Option::as_ref)
+    }
+
+    /// Returns the index and value of every id that has not been freed by
+    /// `remove`, in id order.
+    fn live(&self) -> Slots<'_, T> {
+        Slots::new(&self.item_id_to_str, self.item_str_to_id.len())
+    }
 }
 
 impl<T> Itemizer<T>
@@ -41,98 +145,277 @@ where
     ///
     /// let itemizer: Itemizer<String> = Itemizer::new();
     /// assert_eq!(itemizer.len(), 0);
+    /// ```
     ///
     pub fn new() -> Itemizer<T> {
         Itemizer {
             next_item_id: 0,
-            item_str_to_id: FnvHashMap::default(),
+            item_str_to_id: HashMap::default(),
             item_id_to_str: vec![],
+            free_ids: vec![],
+            counts: vec![],
+            normalizer: None,
+            max_items: None,
+            stats: ItemizerStats::default(),
         }
     }
 
-    /// Returns the `Item` for the given item. If the item is not in the
-    /// `Itemizer`, it is added and a new `Item` is returned.
+    /// Creates a new, empty `Itemizer` with room for at least `n` items
+    /// before reallocating.
     ///
     /// # Examples
     ///
     /// ```
     /// use itemizer::Itemizer;
     ///
-    /// let mut itemizer = Itemizer::new();
-    /// let item1 = itemizer.id_of(&"item1".to_string());
-    /// let item2 = itemizer.id_of(&"item2".to_string());
-    /// let item3 = itemizer.id_of(&"item1".to_string());
-    ///
-    /// assert_eq!(item1.as_index(), 0);
-    /// assert_eq!(item2.as_index(), 1);
-    /// assert_eq!(item3.as_index(), 0);
-    /// assert_eq!(itemizer.len(), 2);
+    /// let itemizer: Itemizer<String> = Itemizer::with_capacity(100);
+    /// assert_eq!(itemizer.len(), 0);
     /// ```
     ///
-    pub fn id_of(&mut self, item: &T) -> Item {
-        if let Some(id) = self.item_str_to_id.get(item) {
-            return *id;
+    pub fn with_capacity(n: usize) -> Itemizer<T> {
+        Itemizer {
+            next_item_id: 0,
+            item_str_to_id: HashMap::with_capacity_and_hasher(n, Default::default()),
+            item_id_to_str: Vec::with_capacity(n),
+            free_ids: vec![],
+            counts: vec![],
+            normalizer: None,
+            max_items: None,
+            stats: ItemizerStats::default(),
         }
+    }
 
-        let id = self.next_item_id;
-        self.next_item_id += 1;
+    /// Creates a new, empty `Itemizer` that looks up items by the key
+    /// `normalizer` maps them to, so that items with the same key share an
+    /// `Item`. The value of an `Item` is the first item seen for its key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::with_normalizer(|item: &String| item.trim().to_string());
+    /// let item1 = itemizer.id_of(&"a ".to_string());
+    /// let item2 = itemizer.id_of(&"a".to_string());
+    ///
+    /// assert_eq!(item1, item2);
+    /// assert_eq!(itemizer.value_of(&item1), "a ");
+    /// ```
+    ///
+    pub fn with_normalizer<F>(normalizer: F) -> Itemizer<T>
+    where
+        F: Fn(&T) -> T + Send + Sync + 'static,
+    {
+        let mut itemizer = Itemizer::new();
+        itemizer.normalizer = Some(Arc::new(normalizer));
+        itemizer
+    }
 
-        self.item_str_to_id.insert(item.clone(), Item::with_id(id));
+    /// Creates a new, empty `Itemizer` that holds at most `n` distinct items
+    /// when they are added through `try_id_of_bounded`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::with_max_items(1);
+    /// itemizer.try_id_of_bounded(&"item1".to_string()).unwrap();
+    ///
+    /// assert!(itemizer.try_id_of_bounded(&"item2".to_string()).is_err());
+    /// ```
+    ///
+    pub fn with_max_items(n: usize) -> Itemizer<T> {
+        let mut itemizer = Itemizer::new();
+        itemizer.max_items = Some(n);
+        itemizer
+    }
 
-        self.item_id_to_str.push(item.clone());
+    /// Creates an `Itemizer` from values that are already in id order, i.e.
+    /// the value at index `i` is assigned id `i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` contains duplicates, or more than `u32::MAX`
+    /// values, the most `id_of` can assign before the id space is exhausted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let itemizer = Itemizer::from_values(vec!["a", "b", "c"]);
+    ///
+    /// assert_eq!(itemizer.id_of_opt(&"b").unwrap().as_index(), 1);
+    /// assert_eq!(itemizer.len(), 3);
+    /// ```
+    ///
+    pub fn from_values(values: Vec<T>) -> Itemizer<T> {
+        let next_item_id = match u32::try_from(values.len()) {
+            Ok(next_item_id) => next_item_id,
+            Err(_) => panic!("{}", ItemizerError::IdSpaceExhausted),
+        };
 
-        assert_eq!(self.item_id_to_str.len(), (id + 1) as usize);
+        let mut item_str_to_id =
+            HashMap::with_capacity_and_hasher(values.len(), Default::default());
+        for (index, value) in values.iter().enumerate() {
+            if item_str_to_id
+                .insert(value.clone(), Item::with_id(index as u32))
+                .is_some()
+            {
+                panic!("duplicate value at index {} in itemizer values", index);
+            }
+        }
 
-        Item::with_id(id)
+        Itemizer {
+            next_item_id,
+            item_str_to_id,
+            item_id_to_str: values.into_iter().map(Some).collect(),
+            free_ids: vec![],
+            counts: vec![],
+            normalizer: None,
+            max_items: None,
+            stats: ItemizerStats::default(),
+        }
     }
 
-    /// Returns the `Item` for the given item if it exists in the `Itemizer`.
-    /// If the item is not in the `Itemizer`, `None` is returned.
+    /// Creates an `Itemizer` from a map of values to their ids, or any other
+    /// iterator of `(value, id)` pairs.
+    ///
+    /// # Errors
+    ///
+    /// The ids must be dense and ascending from 0. Returns
+    /// `ItemizerError::IdOccupied` if two values have the same id,
+    /// `ItemizerError::DuplicateValue` if a value has two ids, and
+    /// `ItemizerError::NotDense` with an id that no value has otherwise.
     ///
     /// # Examples
     ///
     /// ```
     /// use itemizer::Itemizer;
+    /// use std::collections::HashMap;
     ///
-    /// let mut itemizer = Itemizer::new();
-    /// let item1 = itemizer.id_of(&"item1".to_string());
-    /// let item2 = itemizer.id_of_opt(&"item2".to_string());
-    /// let item3 = itemizer.id_of_opt(&"item1".to_string());
+    /// let map: HashMap<&str, u32> = [("a", 1), ("b", 0)].into_iter().collect();
+    /// let itemizer = Itemizer::from_id_map(map).unwrap();
     ///
-    /// assert_eq!(item1.as_index(), 0);
-    /// assert_eq!(item2, None);
-    /// assert_eq!(item3, Some(item1));
-    /// assert_eq!(itemizer.len(), 1);
+    /// assert_eq!(itemizer.values(), &[Some("b"), Some("a")]);
     /// ```
     ///
-    pub fn id_of_opt(&self, item: &T) -> Option<Item> {
-        if let Some(id) = self.item_str_to_id.get(item) {
-            return Some(*id);
-        } else {
-            None
+    pub fn from_id_map<M>(map: M) -> Result<Itemizer<T>, ItemizerError>
+    where
+        M: IntoIterator<Item = (T, u32)>,
+    {
+        let pairs: Vec<(T, u32)> = map.into_iter().collect();
+        let mut slots: Vec<Option<T>> = (0..pairs.len()).map(|_| None).collect();
+        let mut item_str_to_id = HashMap::with_capacity_and_hasher(pairs.len(), Default::default());
+        for (value, id) in pairs {
+            match slots.get_mut(id as usize) {
+                Some(Some(_)) => return Err(ItemizerError::IdOccupied { id: id as usize }),
+                Some(slot) => *slot = Some(value.clone()),
+                // Leaves an id below the end unassigned, which is reported below.
+                None => continue,
+            }
+            if item_str_to_id.insert(value, Item::with_id(id)).is_some() {
+                return Err(ItemizerError::DuplicateValue { id: id as usize });
+            }
+        }
+
+        if let Some(index) = slots.iter().position(Option::is_none) {
+            return Err(ItemizerError::NotDense { id: index });
         }
+        Ok(Itemizer::from_raw_parts(
+            item_str_to_id,
+            slots,
+            vec![],
+            None,
+        ))
     }
 
-    /// Returns the value of the given `Item`.
+    /// Creates an `Itemizer` that assigns ids in sorted order of the given
+    /// values, ignoring duplicates. The same set of values therefore always
+    /// yields the same ids, whatever order they arrive in.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more distinct values than there are `u32` ids.
     ///
     /// # Examples
     ///
     /// ```
     /// use itemizer::Itemizer;
     ///
-    /// let mut itemizer = Itemizer::new();
-    /// let item1 = itemizer.id_of(&"item1".to_string());
-    /// let item2 = itemizer.id_of(&"item2".to_string());
+    /// let itemizer = Itemizer::from_sorted_values(vec!["c", "a", "b", "a"]);
     ///
-    /// assert_eq!(itemizer.value_of(&item1), &"item1".to_string());
-    /// assert_eq!(itemizer.value_of(&item2), &"item2".to_string());
+    /// assert_eq!(itemizer.iter().collect::<Vec<_>>(), vec![&"a", &"b", &"c"]);
     /// ```
     ///
-    pub fn value_of(&self, id: &Item) -> &T {
-        &self.item_id_to_str[id.as_index()]
+    pub fn from_sorted_values(mut values: Vec<T>) -> Itemizer<T>
+    where
+        T: Ord,
+    {
+        values.sort();
+        values.dedup();
+        Itemizer::from_values(values)
     }
 
-    /// Returns the number of items in the `Itemizer`.
+    /// Creates an `Itemizer` that assigns ids in the order of the keys
+    /// `key` maps the given values to, ignoring duplicates. Values with
+    /// equal keys keep the order in which they first occur in `values`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are more distinct values than there are `u32` ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let itemizer = Itemizer::from_values_by_key(vec!["ccc", "a", "bb", "a"], |value| value.len());
+    ///
+    /// assert_eq!(itemizer.iter().collect::<Vec<_>>(), vec![&"a", &"bb", &"ccc"]);
+    /// ```
+    ///
+    pub fn from_values_by_key<K, F>(mut values: Vec<T>, key: F) -> Itemizer<T>
+    where
+        K: Ord,
+        F: Fn(&T) -> K,
+    {
+        values.sort_by_cached_key(|value| key(value));
+        values.into_iter().collect()
+    }
+}
+
+impl Itemizer<String> {
+    /// Creates a new, empty `Itemizer` that ignores the case of its items.
+    /// The value of an `Item` is the spelling it was first seen with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new_case_insensitive();
+    /// let item1 = itemizer.id_of(&"Milk".to_string());
+    /// let item2 = itemizer.id_of(&"MILK".to_string());
+    ///
+    /// assert_eq!(item1, item2);
+    /// assert_eq!(itemizer.value_of(&item1), "Milk");
+    /// ```
+    ///
+    pub fn new_case_insensitive() -> Itemizer<String> {
+        Itemizer::with_normalizer(|item: &String| item.to_lowercase())
+    }
+}
+
+impl<I, S> Itemizer<String, I, S>
+where
+    I: ItemId,
+{
+    /// Returns the `Item` for the given string if it exists in the
+    /// `Itemizer`, like `id_of_opt`, without the need to annotate the
+    /// borrowed type. No `String` is allocated unless the `Itemizer` has a
+    /// normalizer.
     ///
     /// # Examples
     ///
@@ -140,20 +423,38 @@ where
     /// use itemizer::Itemizer;
     ///
     /// let mut itemizer = Itemizer::new();
-    /// assert_eq!(itemizer.len(), 0);
-    /// itemizer.id_of(&"item1".to_string());
-    /// assert_eq!(itemizer.len(), 1);
-    /// itemizer.id_of(&"item2".to_string());
-    /// assert_eq!(itemizer.len(), 2);
-    /// itemizer.id_of(&"item1".to_string());
-    /// assert_eq!(itemizer.len(), 2);
+    /// let milk = itemizer.id_of(&"milk".to_string());
+    ///
+    /// assert_eq!(itemizer.id_of_str("milk"), Some(milk));
+    /// assert_eq!(itemizer.id_of_str("bread"), None);
     /// ```
     ///
-    pub fn len(&self) -> usize {
-        self.item_id_to_str.len()
+    pub fn id_of_str(&self, s: &str) -> Option<Item<I>>
+    where
+        S: BuildHasher,
+    {
+        self.id_of_opt(s)
+    }
+
+    /// Returns the `Item` for the given string like `try_id_of`, allocating
+    /// a `String` only if it is new or the `Itemizer` has a normalizer.
+    #[cfg(feature = "csv")]
+    pub(crate) fn try_id_of_str(&mut self, s: &str) -> Result<Item<I>, ItemizerError>
+    where
+        S: BuildHasher,
+    {
+        let id = self.id_of_str(s);
+        self.count_lookups(1, usize::from(id.is_some()));
+        match id {
+            Some(id) => Ok(id),
+            None => self.insert_new(s.to_owned()),
+        }
     }
 
-    /// Returns an iterator over the items in the `Itemizer`.
+    /// Returns the items that start with the given prefix together with
+    /// their `Item`s, in id order.
+    ///
+    /// This scans all items, so it takes O(n) time.
     ///
     /// # Examples
     ///
@@ -161,96 +462,4227 @@ where
     /// use itemizer::Itemizer;
     ///
     /// let mut itemizer = Itemizer::new();
-    /// itemizer.id_of(&"item1".to_string());
-    /// itemizer.id_of(&"item2".to_string());
+    /// itemizer.id_of(&"apple".to_string());
+    /// let apricot = itemizer.id_of(&"apricot".to_string());
     ///
-    /// let mut iter = itemizer.iter();
-    /// assert_eq!(iter.next(), Some(&"item1".to_string()));
-    /// assert_eq!(iter.next(), Some(&"item2".to_string()));
-    /// assert_eq!(iter.next(), None);
+    /// let mut matches = itemizer.with_prefix("apr");
+    /// assert_eq!(matches.next(), Some((apricot, &"apricot".to_string())));
+    /// assert_eq!(matches.next(), None);
     /// ```
     ///
-    pub fn iter(&self) -> Iter<'_, T> {
-        self.item_id_to_str.iter()
+    pub fn with_prefix(&self, prefix: &str) -> impl Iterator<Item = (Item<I>, &String)> + '_ {
+        let prefix = prefix.to_owned();
+        self.live()
+            .map(|(index, value)| (Item::at_index(index), value))
+            .filter(move |(_, value)| value.starts_with(prefix.as_str()))
     }
 }
 
-impl Debug for Itemizer<String> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for (key, value) in &self.item_str_to_id {
-            writeln!(f, "{}: {}", key, value.as_index())?;
-        }
-        Ok(())
+impl<T, S> Itemizer<T, u32, S>
+where
+    T: Eq + Hash + Clone,
+    S: BuildHasher,
+{
+    /// Creates a new, empty `Itemizer` that hashes its items with the given
+    /// hasher instead of FNV.
+    ///
+    /// Note that ids are assigned in insertion order whatever the hasher, so
+    /// even a randomly seeded hasher yields reproducible ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    /// use std::collections::hash_map::RandomState;
+    ///
+    /// let mut itemizer = Itemizer::with_hasher(RandomState::new());
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// assert_eq!(item1.as_index(), 0);
+    /// ```
+    ///
+    pub fn with_hasher(hasher: S) -> Itemizer<T, u32, S> {
+        Itemizer::from_raw_parts(HashMap::with_hasher(hasher), vec![], vec![], None)
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+impl<T, I, S> Itemizer<T, I, S>
+where
+    T: Eq + Hash + Clone,
+    I: ItemId,
+    S: BuildHasher,
+{
+    /// Returns the key the given item is stored under in `item_str_to_id`.
+    pub(crate) fn key<'a>(&self, item: &'a T) -> Cow<'a, T> {
+        match &self.normalizer {
+            Some(normalizer) => Cow::Owned(normalizer(item)),
+            None => Cow::Borrowed(item),
+        }
+    }
 
-    #[test]
-    fn test_new_itemizer() {
-        let itemizer: Itemizer<String> = Itemizer::new();
-        assert_eq!(itemizer.len(), 0);
+    /// Returns the `Item` for the given item. If the item is not in the
+    /// `Itemizer`, it is added and a new `Item` is returned.
+    ///
+    /// Ids freed by `remove` are reused before new ids are allocated.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all ids of the id type have been assigned. Use `try_id_of` to handle
+    /// this case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// let item2 = itemizer.id_of(&"item2".to_string());
+    /// let item3 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// assert_eq!(item1.as_index(), 0);
+    /// assert_eq!(item2.as_index(), 1);
+    /// assert_eq!(item3.as_index(), 0);
+    /// assert_eq!(itemizer.len(), 2);
+    /// ```
+    ///
+    pub fn id_of(&mut self, item: &T) -> Item<I> {
+        match self.try_id_of(item) {
+            Ok(id) => id,
+            Err(err) => panic!("{}", err),
+        }
     }
 
-    #[test]
-    fn test_id_of() {
-        let mut itemizer = Itemizer::new();
-        let item1 = itemizer.id_of(&"item1".to_string());
-        let item2 = itemizer.id_of(&"item2".to_string());
-        let item3 = itemizer.id_of(&"item1".to_string());
+    /// Returns the `Item` for the given item like `id_of`, but fails instead
+    /// of panicking if no more ids are available.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.try_id_of(&"item1".to_string());
+    ///
+    /// assert_eq!(item1.unwrap().as_index(), 0);
+    /// ```
+    ///
+    pub fn try_id_of(&mut self, item: &T) -> Result<Item<I>, ItemizerError> {
+        if let Some(id) = self.find(item) {
+            return Ok(id);
+        }
 
-        assert_eq!(item1.as_index(), 0);
-        assert_eq!(item2.as_index(), 1);
-        assert_eq!(item3.as_index(), 0);
-        assert_eq!(itemizer.len(), 2);
+        self.insert_new(item.clone())
     }
 
-    #[test]
-    fn test_id_of_opt() {
-        let mut itemizer = Itemizer::new();
-        let item1 = itemizer.id_of(&"item1".to_string());
-        let item2 = itemizer.id_of_opt(&"item2".to_string());
-        let item3 = itemizer.id_of_opt(&"item1".to_string());
+    /// Returns the `Item` for the given item like `try_id_of`, but fails
+    /// instead of adding it if the `Itemizer` already holds as many distinct
+    /// items as allowed by `with_max_items`. Items that are already present
+    /// are always found.
+    ///
+    /// The limit only applies to this method, so e.g. `id_of` can still add
+    /// items beyond it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Itemizer, ItemizerError};
+    ///
+    /// let mut itemizer = Itemizer::with_max_items(1);
+    /// let item1 = itemizer.try_id_of_bounded(&"item1".to_string()).unwrap();
+    ///
+    /// assert_eq!(itemizer.try_id_of_bounded(&"item1".to_string()).unwrap(), item1);
+    /// assert!(matches!(
+    ///     itemizer.try_id_of_bounded(&"item2".to_string()),
+    ///     Err(ItemizerError::CapacityExceeded)
+    /// ));
+    /// ```
+    ///
+    pub fn try_id_of_bounded(&mut self, item: &T) -> Result<Item<I>, ItemizerError> {
+        if let Some(id) = self.find(item) {
+            return Ok(id);
+        }
 
-        assert_eq!(item1.as_index(), 0);
-        assert_eq!(item2, None);
-        assert_eq!(item3, Some(Item::with_id(0)));
+        match self.max_items {
+            Some(max_items) if self.item_str_to_id.len() >= max_items => {
+                Err(ItemizerError::CapacityExceeded)
+            }
+            _ => self.insert_new(item.clone()),
+        }
     }
 
-    #[test]
-    fn test_value_of() {
-        let mut itemizer = Itemizer::new();
-        let item1 = itemizer.id_of(&"item1".to_string());
-        let item2 = itemizer.id_of(&"item2".to_string());
+    /// Returns the `Item` for the given item like `id_of`, but takes
+    /// ownership of it. The item is only cloned once if it is added, and not
+    /// at all if it is already present.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of_owned("item1".to_string());
+    /// let item2 = itemizer.id_of_owned("item1".to_string());
+    ///
+    /// assert_eq!(item1, item2);
+    /// assert_eq!(itemizer.len(), 1);
+    /// ```
+    ///
+    pub fn id_of_owned(&mut self, item: T) -> Item<I> {
+        if let Some(id) = self.find(&item) {
+            return id;
+        }
 
-        assert_eq!(itemizer.value_of(&item1), &"item1".to_string());
-        assert_eq!(itemizer.value_of(&item2), &"item2".to_string());
+        match self.insert_new(item) {
+            Ok(id) => id,
+            Err(err) => panic!("{}", err),
+        }
     }
 
-    #[test]
-    fn test_len() {
+    /// Returns the `Item` for the given item like `id_of`, together with
+    /// `true` if the item was newly added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let (item1, inserted) = itemizer.id_of_entry(&"item1".to_string());
+    /// assert!(inserted);
+    ///
+    /// assert_eq!(itemizer.id_of_entry(&"item1".to_string()), (item1, false));
+    /// ```
+    ///
+    pub fn id_of_entry(&mut self, item: &T) -> (Item<I>, bool) {
+        if let Some(id) = self.find(item) {
+            return (id, false);
+        }
+
+        match self.insert_new(item.clone()) {
+            Ok(id) => (id, true),
+            Err(err) => panic!("{}", err),
+        }
+    }
+
+    /// Looks up the given item to intern it, counting the lookup in `stats`.
+    fn find(&mut self, item: &T) -> Option<Item<I>> {
+        let id = self.item_str_to_id.get(&*self.key(item)).copied();
+        self.count_lookups(1, usize::from(id.is_some()));
+        id
+    }
+
+    /// Counts `lookups` in `stats`, `hits` of which found their item.
+    pub(crate) fn count_lookups(&mut self, lookups: usize, hits: usize) {
+        self.stats.lookups += lookups as u64;
+        self.stats.hits += hits as u64;
+    }
+
+    /// Assigns an id to an item that is known not to be in the `Itemizer`.
+    pub(crate) fn insert_new(&mut self, item: T) -> Result<Item<I>, ItemizerError> {
+        if let Some(id) = self.free_ids.pop() {
+            self.stats.inserts += 1;
+            let id = Item::at_index(id.to_usize());
+            self.item_str_to_id.insert(self.key(&item).into_owned(), id);
+            self.item_id_to_str[id.as_index()] = Some(item);
+            return Ok(id);
+        }
+
+        let id = Item::at_index(self.next_item_id.to_usize());
+        self.next_item_id =
+            I::from_usize(id.as_index() + 1).ok_or(ItemizerError::IdSpaceExhausted)?;
+        self.stats.inserts += 1;
+
+        self.item_str_to_id.insert(self.key(&item).into_owned(), id);
+
+        self.item_id_to_str.push(Some(item));
+
+        assert_eq!(self.item_id_to_str.len(), id.as_index() + 1);
+
+        Ok(id)
+    }
+
+    /// Returns the `Item` for the given item like `id_of`, and counts how
+    /// often the item has been seen this way.
+    ///
+    /// Counting is opt-in: items added through other methods have a count
+    /// of 0 until they are passed to `count_of`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.count_of(&"item1".to_string());
+    /// itemizer.count_of(&"item1".to_string());
+    ///
+    /// assert_eq!(itemizer.count(&item1), 2);
+    /// ```
+    ///
+    pub fn count_of(&mut self, item: &T) -> Item<I> {
+        let id = self.id_of(item);
+        if self.counts.len() <= id.as_index() {
+            self.counts.resize(id.as_index() + 1, 0);
+        }
+        let count = &mut self.counts[id.as_index()];
+        *count = count.saturating_add(1);
+        id
+    }
+
+    /// Returns how often the given `Item` has been seen by `count_of`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// assert_eq!(itemizer.count(&item1), 0);
+    ///
+    /// itemizer.count_of(&"item1".to_string());
+    /// assert_eq!(itemizer.count(&item1), 1);
+    /// ```
+    ///
+    pub fn count(&self, item: &Item<I>) -> u32 {
+        self.counts.get(item.as_index()).copied().unwrap_or(0)
+    }
+
+    /// Returns the `Item` with the highest count from `count_of`, together
+    /// with its count. Ties go to the lowest id. Returns `None` if no item
+    /// has been counted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.count_of(&"milk");
+    /// let bread = itemizer.count_of(&"bread");
+    /// itemizer.count_of(&"bread");
+    ///
+    /// assert_eq!(itemizer.most_frequent(), Some((bread, 2)));
+    /// ```
+    ///
+    pub fn most_frequent(&self) -> Option<(Item<I>, u32)> {
+        self.counts
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .min_by_key(|(index, count)| (core::cmp::Reverse(**count), *index))
+            .map(|(index, count)| (Item::at_index(index), *count))
+    }
+
+    /// Returns the `Item` for the given item if it exists in the `Itemizer`.
+    /// If the item is not in the `Itemizer`, `None` is returned.
+    ///
+    /// The item may be passed in any borrowed form of `T`, e.g. as `&str` for
+    /// an `Itemizer<String>`. If the `Itemizer` has a normalizer, the item is
+    /// converted into a `T` first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// let item2 = itemizer.id_of_opt(&"item2".to_string());
+    /// let item3 = itemizer.id_of_opt(&"item1".to_string());
+    ///
+    /// assert_eq!(item1.as_index(), 0);
+    /// assert_eq!(item2, None);
+    /// assert_eq!(item3, Some(item1));
+    /// assert_eq!(itemizer.id_of_opt("item1"), Some(item1));
+    /// assert_eq!(itemizer.len(), 1);
+    /// ```
+    ///
+    pub fn id_of_opt<Q>(&self, item: &Q) -> Option<Item<I>>
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = T> + ?Sized,
+    {
+        match &self.normalizer {
+            Some(normalizer) => self
+                .item_str_to_id
+                .get(normalizer(&item.to_owned()).borrow())
+                .copied(),
+            None => self.item_str_to_id.get(item).copied(),
+        }
+    }
+
+    /// Returns `true` if the given item has already been assigned an `Item`.
+    ///
+    /// Unlike `id_of`, this never inserts the item. Like `id_of_opt`, it
+    /// accepts any borrowed form of `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// assert!(!itemizer.contains(&"item1".to_string()));
+    /// itemizer.id_of(&"item1".to_string());
+    /// assert!(itemizer.contains(&"item1".to_string()));
+    /// assert!(itemizer.contains("item1"));
+    /// ```
+    ///
+    pub fn contains<Q>(&self, item: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: Eq + Hash + ToOwned<Owned = T> + ?Sized,
+    {
+        self.id_of_opt(item).is_some()
+    }
+
+    /// Returns `true` if the id of the given `Item` is in range for this
+    /// `Itemizer`, i.e. below `len()`.
+    ///
+    /// This is the id-side complement of `contains` and takes O(1) time. Ids
+    /// freed by `remove` are still in range; use `value_of_opt` to rule them
+    /// out as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// assert!(itemizer.contains_id(&item1));
+    /// assert!(!itemizer.contains_id(&Item::with_id(1)));
+    /// ```
+    ///
+    pub fn contains_id(&self, id: &Item<I>) -> bool {
+        id.as_index() < self.item_id_to_str.len()
+    }
+
+    /// Returns the `Item` for the given item like `id_of_opt`, but fails with
+    /// an error naming the item if it is not in the `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1");
+    ///
+    /// assert_eq!(itemizer.id_of_required(&"item1").unwrap(), item1);
+    /// assert_eq!(
+    ///     itemizer.id_of_required(&"item2").unwrap_err().to_string(),
+    ///     "unknown item \"item2\""
+    /// );
+    /// ```
+    ///
+    pub fn id_of_required(&self, item: &T) -> Result<Item<I>, ItemizerError>
+    where
+        T: Debug,
+    {
+        self.id_of_opt(item)
+            .ok_or_else(|| ItemizerError::UnknownItem(format!("{:?}", item)))
+    }
+
+    /// Returns the `Item`s for all given items in order, adding the ones
+    /// that are not yet in the `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let items = itemizer.ids_of(&["a", "b", "a"]);
+    ///
+    /// assert_eq!(items, vec![itemizer.id_of(&"a"), itemizer.id_of(&"b"), itemizer.id_of(&"a")]);
+    /// assert_eq!(itemizer.len(), 2);
+    /// ```
+    ///
+    pub fn ids_of(&mut self, items: &[T]) -> Vec<Item<I>> {
+        items.iter().map(|item| self.id_of(item)).collect()
+    }
+
+    /// Returns the `Item`s for all given items in order, with `None` for the
+    /// ones that are not in the `Itemizer`. Unlike `ids_of`, this never adds
+    /// items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let a = itemizer.id_of(&"a");
+    ///
+    /// assert_eq!(itemizer.ids_of_opt(&["a", "b"]), vec![Some(a), None]);
+    /// assert_eq!(itemizer.len(), 1);
+    /// ```
+    ///
+    pub fn ids_of_opt(&self, items: &[T]) -> Vec<Option<Item<I>>> {
+        items.iter().map(|item| self.id_of_opt(item)).collect()
+    }
+
+    /// Returns the `Item`s for all given items in order like `ids_of`, but
+    /// takes the items by value so that new items are moved into the
+    /// `Itemizer` instead of cloned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let items = itemizer.ids_of_owned(vec!["a".to_string(), "b".to_string(), "a".to_string()]);
+    ///
+    /// assert_eq!(items[0], items[2]);
+    /// assert_eq!(itemizer.len(), 2);
+    /// ```
+    ///
+    pub fn ids_of_owned(&mut self, items: Vec<T>) -> Vec<Item<I>> {
+        items
+            .into_iter()
+            .map(|item| self.id_of_owned(item))
+            .collect()
+    }
+
+    /// Returns the distinct `Item`s of the given items in order of their
+    /// first occurrence, adding the ones that are not yet in the `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let items = itemizer.distinct_ids_of(&["a", "b", "a"]);
+    ///
+    /// assert_eq!(items, vec![itemizer.id_of(&"a"), itemizer.id_of(&"b")]);
+    /// ```
+    ///
+    pub fn distinct_ids_of(&mut self, items: &[T]) -> Vec<Item<I>> {
+        let mut seen = HashSet::<_, FnvBuildHasher>::default();
+        items
+            .iter()
+            .map(|item| self.id_of(item))
+            .filter(|id| seen.insert(*id))
+            .collect()
+    }
+
+    /// Returns the `Item` of every item of a weighted transaction together
+    /// with its weight, adding the items that are not yet in the `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let items = itemizer.itemize_weighted(&[("milk", 3), ("bread", 1)]);
+    ///
+    /// assert_eq!(items, vec![(itemizer.id_of(&"milk"), 3), (itemizer.id_of(&"bread"), 1)]);
+    /// ```
+    ///
+    pub fn itemize_weighted(&mut self, pairs: &[(T, u32)]) -> Vec<(Item<I>, u32)> {
+        pairs
+            .iter()
+            .map(|(item, weight)| (self.id_of(item), *weight))
+            .collect()
+    }
+
+    /// Adds the given items, which must all be new, under consecutive ids
+    /// and returns the range of their `Item`s. Unlike `id_of`, this never
+    /// reuses ids freed by `remove`, so that the ids stay contiguous.
+    ///
+    /// # Panics
+    ///
+    /// Panics if any of the items is already in the `Itemizer` or occurs
+    /// twice in `items`, or if there are not enough ids left. The `Itemizer`
+    /// is left unchanged in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"a");
+    /// let range = itemizer.intern_new_block(&["b", "c"]);
+    ///
+    /// for (item, value) in range.into_iter().zip(["b", "c"]) {
+    ///     assert_eq!(itemizer.value_of(&item), &value);
+    /// }
+    /// ```
+    ///
+    pub fn intern_new_block(&mut self, items: &[T]) -> ItemRange<I> {
+        let mut keys = HashSet::<_, FnvBuildHasher>::default();
+        for (index, item) in items.iter().enumerate() {
+            let key = self.key(item);
+            if self.item_str_to_id.contains_key(&*key) || !keys.insert(key) {
+                panic!("value at index {} of the block is not new", index);
+            }
+        }
+        let start = self.item_id_to_str.len();
+        let next_item_id = match I::from_usize(start + items.len()) {
+            Some(next_item_id) => next_item_id,
+            None => panic!("{}", ItemizerError::IdSpaceExhausted),
+        };
+
+        for item in items {
+            let id = Item::at_index(self.item_id_to_str.len());
+            self.item_str_to_id.insert(self.key(item).into_owned(), id);
+            self.item_id_to_str.push(Some(item.clone()));
+        }
+        self.next_item_id = next_item_id;
+        self.stats.lookups += items.len() as u64;
+        self.stats.inserts += items.len() as u64;
+        ItemRange::new(Item::at_index(start), items.len())
+    }
+
+    /// Adds all given items that are not yet in the `Itemizer`, and returns
+    /// how many of them were added.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"a");
+    ///
+    /// assert_eq!(itemizer.extend_counting(&["a", "b", "c", "b"]), 2);
+    /// ```
+    ///
+    pub fn extend_counting(&mut self, items: &[T]) -> usize {
+        items.iter().filter(|item| self.id_of_entry(item).1).count()
+    }
+
+    /// Encodes all transactions into one flat vector of ids plus row
+    /// offsets, adding items that are not yet in the `Itemizer`.
+    ///
+    /// The ids of transaction `i` are `ids[offsets[i]..offsets[i + 1]]`, so
+    /// `offsets` has one more entry than there are transactions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let (ids, offsets) = itemizer.itemize_csr(vec![vec!["a", "b"], vec!["b"]]);
+    ///
+    /// assert_eq!(ids, vec![0, 1, 1]);
+    /// assert_eq!(offsets, vec![0, 2, 3]);
+    /// ```
+    ///
+    pub fn itemize_csr<It, Tx>(&mut self, transactions: It) -> (Vec<I>, Vec<usize>)
+    where
+        It: IntoIterator<Item = Tx>,
+        Tx: IntoIterator<Item = T>,
+    {
+        let mut ids = vec![];
+        let mut offsets = vec![0];
+        for transaction in transactions {
+            ids.extend(
+                transaction
+                    .into_iter()
+                    .map(|item| self.id_of_owned(item).id()),
+            );
+            offsets.push(ids.len());
+        }
+        (ids, offsets)
+    }
+
+    /// Adds the given item with the given id, e.g. to keep ids aligned with
+    /// an external system. Ids between the current end of the id space and
+    /// `id` are reserved as free ids, which `id_of` hands out before new
+    /// ones. Like ids freed by `remove`, they have no value until then.
+    ///
+    /// Inserting an item again at the id it already has is a no-op.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ItemizerError::IdOccupied` if `id` is assigned to another
+    /// value, `ItemizerError::DuplicateValue` if the item already has
+    /// another id, and `ItemizerError::IdSpaceExhausted` if `id` is the
+    /// largest id of the id type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item = itemizer.insert_at(3, "d").unwrap();
+    ///
+    /// assert_eq!(item.as_index(), 3);
+    /// assert_eq!(itemizer.len(), 4);
+    /// assert!(itemizer.insert_at(3, "e").is_err());
+    /// ```
+    ///
+    pub fn insert_at(&mut self, id: I, item: T) -> Result<Item<I>, ItemizerError> {
+        let index = id.to_usize();
+        let key = self.key(&item).into_owned();
+        if let Some(&existing) = self.item_str_to_id.get(&key) {
+            if existing.as_index() == index {
+                return Ok(existing);
+            }
+            return Err(ItemizerError::DuplicateValue { id: index });
+        }
+
+        if index < self.item_id_to_str.len() {
+            let position = self
+                .free_ids
+                .iter()
+                .position(|free| free.to_usize() == index)
+                .ok_or(ItemizerError::IdOccupied { id: index })?;
+            self.free_ids.remove(position);
+            self.item_id_to_str[index] = Some(item);
+        } else {
+            let next_item_id = I::from_usize(index + 1).ok_or(ItemizerError::IdSpaceExhausted)?;
+            for gap in self.item_id_to_str.len()..index {
+                self.free_ids.push(Item::<I>::at_index(gap).id());
+            }
+            self.item_id_to_str.resize_with(index, || None);
+            self.item_id_to_str.push(Some(item));
+            self.next_item_id = next_item_id;
+        }
+
+        let id = Item::at_index(index);
+        self.item_str_to_id.insert(key, id);
+        Ok(id)
+    }
+
+    /// Removes the given item from the `Itemizer`, returning the `Item` it
+    /// was assigned to, or `None` if it was not present.
+    ///
+    /// The ids of all other items are left untouched, so existing `Item`s
+    /// stay valid. The freed id is handed out again by the next `id_of` call
+    /// for a new item. Until then, it has no value: it is still counted by
+    /// `len`, but skipped by `iter` and the other iterators.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// let item2 = itemizer.id_of(&"item2".to_string());
+    ///
+    /// assert_eq!(itemizer.remove(&"item1".to_string()), Some(item1));
+    /// assert_eq!(itemizer.remove(&"item1".to_string()), None);
+    /// assert_eq!(itemizer.value_of(&item2), &"item2".to_string());
+    ///
+    /// let item3 = itemizer.id_of(&"item3".to_string());
+    /// assert_eq!(item3, item1);
+    /// ```
+    ///
+    pub fn remove(&mut self, item: &T) -> Option<Item<I>> {
+        let id = self.item_str_to_id.remove(&*self.key(item))?;
+        self.item_id_to_str[id.as_index()] = None;
+        self.free_ids.push(id.id());
+        if let Some(count) = self.counts.get_mut(id.as_index()) {
+            *count = 0;
+        }
+        Some(id)
+    }
+
+    /// Returns the value of the given `Item`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Item` is out of range or its id has been freed by
+    /// `remove`. Use `value_of_opt` to handle these cases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// let item2 = itemizer.id_of(&"item2".to_string());
+    ///
+    /// assert_eq!(itemizer.value_of(&item1), &"item1".to_string());
+    /// assert_eq!(itemizer.value_of(&item2), &"item2".to_string());
+    /// ```
+    ///
+    pub fn value_of(&self, id: &Item<I>) -> &T {
+        &self[*id]
+    }
+
+    /// Returns a copy of the value of the given `Item`, for items of a
+    /// `Copy` type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer: Itemizer<i32> = Itemizer::new();
+    /// let item = itemizer.id_of(&42);
+    ///
+    /// let value: i32 = itemizer.value_copied(&item);
+    /// assert_eq!(value, 42);
+    /// ```
+    ///
+    pub fn value_copied(&self, id: &Item<I>) -> T
+    where
+        T: Copy,
+    {
+        *self.value_of(id)
+    }
+
+    /// Returns the value of the given `Item` without checking that it is in
+    /// range and has a value.
+    ///
+    /// # Safety
+    ///
+    /// The `Item` must be in range for this `Itemizer`, i.e. its index must
+    /// be less than `len()`, and must not have been freed by `remove`, for
+    /// instance because it has been returned by this `Itemizer` and no items
+    /// have been dropped since by `remove`, `clear`, `retain`, `compact`,
+    /// `truncate` or `rollback`. Calling this with an out-of-range or freed
+    /// `Item` is undefined behavior.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// // SAFETY: `item1` was just returned by `itemizer`.
+    /// assert_eq!(unsafe { itemizer.value_of_unchecked(&item1) }, "item1");
+    /// ```
+    ///
+    pub unsafe fn value_of_unchecked(&self, id: &Item<I>) -> &T {
+        // SAFETY: the caller guarantees that the index is in range and that
+        // its slot holds a value.
+        unsafe {
+            self.item_id_to_str
+                .get_unchecked(id.as_index())
+                .as_ref()
+                .unwrap_unchecked()
+        }
+    }
+
+    /// Returns the value of the given `Item`, or `None` if the `Item` does
+    /// not belong to this `Itemizer` or has been removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// assert_eq!(itemizer.value_of_opt(&item1), Some(&"item1".to_string()));
+    /// assert_eq!(itemizer.value_of_opt(&Item::with_id(1)), None);
+    /// ```
+    ///
+    pub fn value_of_opt(&self, id: &Item<I>) -> Option<&T> {
+        self.item_id_to_str.get(id.as_index())?.as_ref()
+    }
+
+    /// Returns the values of all given `Item`s in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let items = itemizer.ids_of(&["a", "b", "a"]);
+    ///
+    /// assert_eq!(itemizer.decode(&items), vec![&"a", &"b", &"a"]);
+    /// ```
+    ///
+    pub fn decode(&self, items: &[Item<I>]) -> Vec<&T> {
+        items.iter().map(|item| self.value_of(item)).collect()
+    }
+
+    /// Returns the values of all given `Item`s in order, with `None` for
+    /// `Item`s that do not belong to this `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"a");
+    ///
+    /// assert_eq!(itemizer.decode_opt(&[item1, Item::with_id(7)]), vec![Some(&"a"), None]);
+    /// ```
+    ///
+    pub fn decode_opt(&self, items: &[Item<I>]) -> Vec<Option<&T>> {
+        items.iter().map(|item| self.value_of_opt(item)).collect()
+    }
+
+    /// Returns the values of all given `Item`s in order, with `default` for
+    /// `Item`s that do not belong to this `Itemizer`, e.g. because they come
+    /// from another one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"a");
+    ///
+    /// assert_eq!(itemizer.decode_or(&[item1, Item::with_id(7)], &"?"), vec![&"a", &"?"]);
+    /// ```
+    ///
+    pub fn decode_or<'a>(&'a self, items: &[Item<I>], default: &'a T) -> Vec<&'a T> {
+        items
+            .iter()
+            .map(|item| self.value_of_opt(item).unwrap_or(default))
+            .collect()
+    }
+
+    /// Returns the values of all given `Item`s in order, or `None` if any of
+    /// them does not belong to this `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let items = itemizer.ids_of(&["a", "b"]);
+    ///
+    /// assert_eq!(itemizer.value_of_many(&items), Some(vec![&"a", &"b"]));
+    /// assert_eq!(itemizer.value_of_many(&[items[0], Item::with_id(7)]), None);
+    /// ```
+    ///
+    pub fn value_of_many<'a>(&'a self, items: &[Item<I>]) -> Option<Vec<&'a T>> {
+        items.iter().map(|item| self.value_of_opt(item)).collect()
+    }
+
+    /// Encodes transactions as a one-hot matrix with one row per
+    /// transaction and one column per id, which is 1 if the transaction
+    /// contains the item with that id and 0 otherwise.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `Item` is out of range for this `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let transactions = vec![itemizer.ids_of(&["a", "c"]), itemizer.ids_of(&["b"])];
+    ///
+    /// assert_eq!(itemizer.one_hot(&transactions), vec![vec![1, 1, 0], vec![0, 0, 1]]);
+    /// ```
+    ///
+    pub fn one_hot(&self, transactions: &[Vec<Item<I>>]) -> Vec<Vec<u8>> {
+        transactions
+            .iter()
+            .map(|transaction| {
+                let mut row = vec![0; self.item_id_to_str.len()];
+                for item in transaction {
+                    row[item.as_index()] = 1;
+                }
+                row
+            })
+            .collect()
+    }
+
+    /// Returns the support of every id, i.e. the number of transactions
+    /// that contain the item with that id. Items occurring several times in
+    /// one transaction are counted once.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an `Item` is out of range for this `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let transactions = vec![itemizer.ids_of(&["a", "b", "a"]), itemizer.ids_of(&["a"])];
+    ///
+    /// assert_eq!(itemizer.support_counts(&transactions), vec![2, 1]);
+    /// ```
+    ///
+    pub fn support_counts(&self, transactions: &[Vec<Item<I>>]) -> Vec<u32> {
+        let mut counts = vec![0; self.item_id_to_str.len()];
+        let mut last_seen = vec![usize::MAX; self.item_id_to_str.len()];
+        for (index, transaction) in transactions.iter().enumerate() {
+            for item in transaction {
+                if last_seen[item.as_index()] != index {
+                    last_seen[item.as_index()] = index;
+                    counts[item.as_index()] += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// Returns the `Item` that `id_of` would assign to an item that is not
+    /// yet in the `Itemizer`.
+    ///
+    /// This is `len()` for a dense `Itemizer`, but an id freed by `remove`
+    /// is handed out again before the id space grows.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    ///
+    /// let next = itemizer.next_id();
+    /// assert_eq!(next.as_index(), itemizer.len());
+    /// assert_eq!(itemizer.id_of(&"item2".to_string()), next);
+    /// ```
+    ///
+    pub fn next_id(&self) -> Item<I> {
+        match self.free_ids.last() {
+            Some(id) => Item::at_index(id.to_usize()),
+            None => Item::at_index(self.next_item_id.to_usize()),
+        }
+    }
+
+    /// Returns the `Item` that `id_of` would return for the given item,
+    /// and whether the item would be newly added, without changing the
+    /// `Itemizer`. For a new item, this is `next_id()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// assert_eq!(itemizer.would_assign(&"item1".to_string()), (item1, false));
+    /// assert_eq!(itemizer.would_assign(&"item2".to_string()), (itemizer.next_id(), true));
+    /// assert_eq!(itemizer.len(), 1);
+    /// ```
+    ///
+    pub fn would_assign(&self, item: &T) -> (Item<I>, bool) {
+        match self.id_of_opt(item) {
+            Some(id) => (id, false),
+            None => (self.next_id(), true),
+        }
+    }
+
+    /// Returns how often items have been looked up to be interned, and how
+    /// many of those lookups found the item or added it.
+    ///
+    /// All methods that add missing items, like `id_of`, `id_of_owned` or
+    /// `ids_of`, are counted. Read-only lookups like `id_of_opt` are not, so
+    /// that they do not need to mutate the `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.ids_of(&["a", "b", "a"]);
+    ///
+    /// let stats = itemizer.stats();
+    /// assert_eq!((stats.lookups, stats.hits, stats.inserts), (3, 1, 2));
+    /// ```
+    ///
+    pub fn stats(&self) -> ItemizerStats {
+        self.stats
+    }
+
+    /// Returns the number of items in the `Itemizer`.
+    ///
+    /// Ids freed by `remove` are counted until they are reused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// assert_eq!(itemizer.len(), 0);
+    /// itemizer.id_of(&"item1".to_string());
+    /// assert_eq!(itemizer.len(), 1);
+    /// itemizer.id_of(&"item2".to_string());
+    /// assert_eq!(itemizer.len(), 2);
+    /// itemizer.id_of(&"item1".to_string());
+    /// assert_eq!(itemizer.len(), 2);
+    /// ```
+    ///
+    pub fn len(&self) -> usize {
+        self.item_id_to_str.len()
+    }
+
+    /// Returns `true` if the `Itemizer` contains no items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// assert!(itemizer.is_empty());
+    /// itemizer.id_of(&"item1".to_string());
+    /// assert!(!itemizer.is_empty());
+    /// ```
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.item_id_to_str.is_empty()
+    }
+
+    /// Returns an iterator over the items in the `Itemizer`, skipping ids
+    /// freed by `remove`.
+    ///
+    /// Items are always yielded in ascending id order, never in the order of
+    /// the hash map. Unless ids freed by `remove` have been reused, this is
+    /// the order in which the items were first seen, so output built from it
+    /// is reproducible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// itemizer.id_of(&"item2".to_string());
+    ///
+    /// let mut iter = itemizer.iter();
+    /// assert_eq!(iter.next(), Some(&"item1".to_string()));
+    /// assert_eq!(iter.next(), Some(&"item2".to_string()));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    ///
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        self.live().map(|(_, value)| value)
+    }
+
+    /// Returns the value of every id of the `Itemizer` as a slice in id
+    /// order, with `None` for ids freed by `remove`.
+    ///
+    /// Since ids are assigned densely from 0, the slice can be indexed by
+    /// `Item::as_index`, i.e. `values()[item.as_index()] == Some(value_of(&item))`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// let item2 = itemizer.id_of(&"item2".to_string());
+    ///
+    /// itemizer.remove(&"item1".to_string());
+    ///
+    /// assert_eq!(itemizer.values(), &[None, Some("item2".to_string())]);
+    /// assert_eq!(itemizer.values()[item2.as_index()].as_ref(), Some(itemizer.value_of(&item2)));
+    /// ```
+    ///
+    pub fn values(&self) -> &[Option<T>] {
+        &self.item_id_to_str
+    }
+
+    /// Consumes the `Itemizer` and returns the value of every id in id order,
+    /// so that the value of an `Item` is at index `item.as_index()`.
+    ///
+    /// Ids freed by `remove` have no value and are `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item2 = itemizer.ids_of(&["a", "b"])[1];
+    ///
+    /// let values = itemizer.into_values();
+    /// assert_eq!(values[item2.as_index()], Some("b"));
+    /// ```
+    ///
+    pub fn into_values(self) -> Vec<Option<T>> {
+        self.item_id_to_str
+    }
+
+    /// Returns an iterator over the `Item`s in the `Itemizer` in id order,
+    /// skipping ids freed by `remove`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer: Itemizer<&str> = vec!["a", "b", "c"].into_iter().collect();
+    /// itemizer.remove(&"b");
+    /// let ids: Vec<Item> = itemizer.iter_ids().collect();
+    ///
+    /// assert_eq!(ids, vec![Item::with_id(0), Item::with_id(2)]);
+    /// ```
+    ///
+    pub fn iter_ids(&self) -> impl DoubleEndedIterator<Item = Item<I>> + ExactSizeIterator + '_ {
+        self.live().map(|(index, _)| Item::at_index(index))
+    }
+
+    /// Returns an iterator over the `Item`s in the `Itemizer` together with
+    /// their values, in id order. Use `rev` to walk from the highest id down.
+    ///
+    /// The order is guaranteed like that of `iter`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// itemizer.id_of(&"item2".to_string());
+    ///
+    /// for (item, value) in itemizer.iter_items() {
+    ///     assert_eq!(itemizer.id_of_opt(value), Some(item));
+    /// }
+    ///
+    /// let mut iter = itemizer.iter_items();
+    /// assert_eq!(iter.next(), Some((Item::with_id(0), &"item1".to_string())));
+    /// assert_eq!(iter.next(), Some((Item::with_id(1), &"item2".to_string())));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    ///
+    pub fn iter_items(&self) -> impl DoubleEndedIterator<Item = (Item<I>, &T)> + ExactSizeIterator {
+        self.live()
+            .map(|(index, value)| (Item::at_index(index), value))
+    }
+
+    /// Returns an owned map from every `Item` to a copy of its value, e.g.
+    /// to move it into another thread. Ids freed by `remove` are left out.
+    ///
+    /// With the `std` feature, the map is an `fnv::FnvHashMap<Item, T>`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// let map = itemizer.to_id_value_map();
+    /// assert_eq!(map[&item1], "item1");
+    /// ```
+    ///
+    pub fn to_id_value_map(&self) -> HashMap<Item<I>, T, FnvBuildHasher> {
+        self.live()
+            .map(|(index, value)| (Item::at_index(index), value.clone()))
+            .collect()
+    }
+
+    /// Returns an iterator over the index and value of every id in the
+    /// `Itemizer`, in id order, skipping ids freed by `remove`.
+    ///
+    /// The order is guaranteed like that of `iter`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// itemizer.id_of(&"item2".to_string());
+    ///
+    /// let mut iter = itemizer.enumerate();
+    /// assert_eq!(iter.next(), Some((0, &"item1".to_string())));
+    /// assert_eq!(iter.next(), Some((1, &"item2".to_string())));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    ///
+    pub fn enumerate(&self) -> impl DoubleEndedIterator<Item = (usize, &T)> + ExactSizeIterator {
+        self.live()
+    }
+
+    /// Returns an iterator over the items in the `Itemizer` and their
+    /// `Item`s, in no particular order. Ids freed by `remove` are skipped.
+    ///
+    /// If the `Itemizer` has a normalizer, the normalized items are returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// itemizer.id_of(&"item2".to_string());
+    ///
+    /// for (value, item) in itemizer.iter_unordered() {
+    ///     assert_eq!(itemizer.value_of(&item), value);
+    /// }
+    /// ```
+    ///
+    pub fn iter_unordered(&self) -> impl Iterator<Item = (&T, Item<I>)> {
+        self.item_str_to_id.iter().map(|(value, id)| (value, *id))
+    }
+
+    /// Removes all items from the `Itemizer`, keeping the allocated memory
+    /// for reuse. The next new item is assigned id 0 again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// itemizer.clear();
+    ///
+    /// assert_eq!(itemizer.len(), 0);
+    /// assert_eq!(itemizer.id_of(&"item2".to_string()).as_index(), 0);
+    /// ```
+    ///
+    pub fn clear(&mut self) {
+        self.next_item_id = Item::<I>::at_index(0).id();
+        self.item_str_to_id.clear();
+        self.item_id_to_str.clear();
+        self.free_ids.clear();
+        self.counts.clear();
+    }
+
+    /// Returns the number of items the `Itemizer` can hold without
+    /// reallocating its values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let itemizer: Itemizer<String> = Itemizer::with_capacity(100);
+    ///
+    /// assert!(itemizer.capacity() >= 100);
+    /// ```
+    ///
+    pub fn capacity(&self) -> usize {
+        self.item_id_to_str.capacity()
+    }
+
+    /// Returns an estimate of the heap memory used by the `Itemizer` in bytes.
+    ///
+    /// This counts the allocated capacity of the map and the vectors, but
+    /// not memory owned by the values themselves, such as the contents of a
+    /// `String`. Use `estimated_heap_bytes_deep` to include it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer: Itemizer<u64> = Itemizer::new();
+    /// assert_eq!(itemizer.estimated_heap_bytes(), 0);
+    ///
+    /// itemizer.id_of(&42);
+    /// assert!(itemizer.estimated_heap_bytes() > 0);
+    /// ```
+    ///
+    pub fn estimated_heap_bytes(&self) -> usize {
+        // Each map bucket holds an entry and a control byte.
+        self.item_str_to_id.capacity() * (size_of::<(T, Item<I>)>() + 1)
+            + self.item_id_to_str.capacity() * size_of::<Option<T>>()
+            + self.free_ids.capacity() * size_of::<I>()
+            + self.counts.capacity() * size_of::<u32>()
+    }
+
+    /// Returns an estimate of the heap memory used by the `Itemizer` in bytes
+    /// like `estimated_heap_bytes`, including the memory owned by the values.
+    ///
+    /// Every value is stored twice, once as a key of the map and once in id
+    /// order, so its heap memory is counted twice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"a rather long item".to_string());
+    ///
+    /// assert!(itemizer.estimated_heap_bytes_deep() >= itemizer.estimated_heap_bytes() + 36);
+    /// ```
+    ///
+    pub fn estimated_heap_bytes_deep(&self) -> usize
+    where
+        T: HeapSize,
+    {
+        self.estimated_heap_bytes()
+            + self
+                .item_str_to_id
+                .keys()
+                .map(HeapSize::heap_size)
+                .sum::<usize>()
+            + self
+                .item_id_to_str
+                .iter()
+                .flatten()
+                .map(HeapSize::heap_size)
+                .sum::<usize>()
+    }
+
+    /// Reserves capacity for at least `additional` more items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer: Itemizer<String> = Itemizer::new();
+    /// itemizer.reserve(100);
+    ///
+    /// assert_eq!(itemizer.len(), 0);
+    /// ```
+    ///
+    pub fn reserve(&mut self, additional: usize) {
+        self.item_str_to_id.reserve(additional);
+        self.item_id_to_str.reserve(additional);
+    }
+
+    /// Shrinks the capacity of the `Itemizer` as much as possible.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer: Itemizer<String> = Itemizer::with_capacity(100);
+    /// itemizer.id_of(&"item1".to_string());
+    /// itemizer.shrink_to_fit();
+    ///
+    /// assert_eq!(itemizer.len(), 1);
+    /// ```
+    ///
+    pub fn shrink_to_fit(&mut self) {
+        self.item_str_to_id.shrink_to_fit();
+        self.item_id_to_str.shrink_to_fit();
+        self.free_ids.shrink_to_fit();
+        self.counts.shrink_to_fit();
+    }
+
+    /// Adds all items of `other` to this `Itemizer` in id order, returning a
+    /// table mapping each id of `other` to the corresponding id in `self`.
+    ///
+    /// Items already present in `self` keep their ids. Ids freed by `remove`
+    /// in `other` are not merged and map to `None`, so the table can be
+    /// passed to `apply_remap` like the one returned by `compact`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer: Itemizer<&str> = vec!["a", "b"].into_iter().collect();
+    /// let other: Itemizer<&str> = vec!["c", "a"].into_iter().collect();
+    ///
+    /// let remap = itemizer.merge(&other);
+    ///
+    /// assert_eq!(remap[0], Some(itemizer.id_of(&"c")));
+    /// assert_eq!(remap[1], Some(itemizer.id_of(&"a")));
+    /// assert_eq!(itemizer.len(), 3);
+    /// ```
+    ///
+    pub fn merge(&mut self, other: &Itemizer<T, I, S>) -> Vec<Option<Item<I>>> {
+        other
+            .slots()
+            .map(|value| Some(self.id_of(value?)))
+            .collect()
+    }
+
+    /// Returns the values that are in both this `Itemizer` and `other`, in
+    /// id order of this `Itemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let itemizer: Itemizer<&str> = vec!["a", "b", "c"].into_iter().collect();
+    /// let other: Itemizer<&str> = vec!["c", "d", "a"].into_iter().collect();
+    ///
+    /// assert_eq!(itemizer.common_items(&other), vec![&"a", &"c"]);
+    /// ```
+    ///
+    pub fn common_items(&self, other: &Itemizer<T, I, S>) -> Vec<&T> {
+        self.slots()
+            .flatten()
+            .filter(|value| other.contains(*value))
+            .collect()
+    }
+
+    /// Returns the values of this `Itemizer` that are not in `baseline`, in
+    /// id order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let baseline: Itemizer<&str> = vec!["a", "b"].into_iter().collect();
+    /// let itemizer: Itemizer<&str> = vec!["b", "c", "a", "d"].into_iter().collect();
+    ///
+    /// assert_eq!(itemizer.new_items_vs(&baseline), vec![&"c", &"d"]);
+    /// ```
+    ///
+    pub fn new_items_vs(&self, baseline: &Itemizer<T, I, S>) -> Vec<&T> {
+        self.slots()
+            .flatten()
+            .filter(|value| !baseline.contains(*value))
+            .collect()
+    }
+
+    /// Returns a new `Itemizer` holding `f` applied to every value, keeping
+    /// all id assignments. Fails if `f` maps two values to the same new
+    /// value, naming the larger of their ids.
+    ///
+    /// The normalizer, counts and stats are not carried over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let itemizer: Itemizer<&str> = vec!["Milk", "Eggs"].into_iter().collect();
+    /// let lower = itemizer.map_values(|value| value.to_lowercase()).unwrap();
+    ///
+    /// assert_eq!(lower.id_of_opt("eggs"), itemizer.id_of_opt(&"Eggs"));
+    /// assert!(itemizer.map_values(|value| value.len()).is_err());
+    /// ```
+    ///
+    pub fn map_values<U, F>(&self, f: F) -> Result<Itemizer<U, I>, ItemizerError>
+    where
+        U: Eq + Hash + Clone,
+        F: Fn(&T) -> U,
+    {
+        let mut item_str_to_id =
+            HashMap::with_capacity_and_hasher(self.item_str_to_id.len(), Default::default());
+        let mut item_id_to_str = Vec::with_capacity(self.item_id_to_str.len());
+        for (index, value) in self.slots().enumerate() {
+            match value {
+                Some(value) => {
+                    let value = f(value);
+                    if item_str_to_id
+                        .insert(value.clone(), Item::at_index(index))
+                        .is_some()
+                    {
+                        return Err(ItemizerError::DuplicateValue { id: index });
+                    }
+                    item_id_to_str.push(Some(value));
+                }
+                None => item_id_to_str.push(None),
+            }
+        }
+
+        Ok(Itemizer::from_raw_parts(
+            item_str_to_id,
+            item_id_to_str,
+            self.free_ids.clone(),
+            None,
+        ))
+    }
+
+    /// Consumes the `Itemizer` and returns its map from values to `Item`s,
+    /// its values in id order and the next id, without copying them. Counts
+    /// and stats are dropped.
+    ///
+    /// With the `std` feature, the map is a `std::collections::HashMap`, so
+    /// the default itemizer yields an `fnv::FnvHashMap<T, Item>`.
+    ///
+    /// # Errors
+    ///
+    /// The parts can represent neither freed ids nor a normalizer, whose
+    /// keys `from_parts` could not check. Returns `ItemizerError::NotDense`
+    /// if ids have been freed by `remove`, so call `compact` first, and
+    /// `ItemizerError::Normalized` if the `Itemizer` has a normalizer. The
+    /// `Itemizer` is dropped in both cases.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// let (map, values, next) = itemizer.into_parts().unwrap();
+    /// assert_eq!(map[&"item1".to_string()], item1);
+    /// assert_eq!(values, vec!["item1".to_string()]);
+    /// assert_eq!(next, 1);
+    /// ```
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn into_parts(self) -> Result<(HashMap<T, Item<I>, S>, Vec<T>, I), ItemizerError> {
+        self.assert_dense()?;
+        if self.normalizer.is_some() {
+            return Err(ItemizerError::Normalized);
+        }
+        let values = self.item_id_to_str.into_iter().flatten().collect();
+        Ok((self.item_str_to_id, values, self.next_item_id))
+    }
+
+    /// Creates an `Itemizer` from parts as returned by `into_parts`, without
+    /// copying them.
+    ///
+    /// The parts are checked with `validate`, so `map` must assign each
+    /// value in `values` its index and hold nothing else, and `next` must be
+    /// the number of values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let itemizer: Itemizer<&str> = vec!["a", "b"].into_iter().collect();
+    /// let (map, values, next) = itemizer.into_parts().unwrap();
+    ///
+    /// let itemizer = Itemizer::from_parts(map.clone(), values.clone(), next).unwrap();
+    /// assert_eq!(itemizer.id_of_opt(&"b"), Some(Item::with_id(1)));
+    /// assert!(Itemizer::from_parts(map, values, 3).is_err());
+    /// ```
+    ///
+    pub fn from_parts(
+        map: HashMap<T, Item<I>, S>,
+        values: Vec<T>,
+        next: I,
+    ) -> Result<Itemizer<T, I, S>, ItemizerError> {
+        let values = values.into_iter().map(Some).collect();
+        let mut itemizer = Itemizer::from_raw_parts(map, values, vec![], None);
+        itemizer.next_item_id = next;
+        itemizer.validate()?;
+        Ok(itemizer)
+    }
+
+    /// Freezes the `Itemizer` into a read-only `FrozenItemizer`, which can
+    /// no longer assign ids to new items but can be shared between threads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// let frozen = itemizer.freeze();
+    /// assert_eq!(frozen.id_of_opt(&"item1".to_string()), Some(item1));
+    /// ```
+    ///
+    pub fn freeze(self) -> FrozenItemizer<T, I, S> {
+        FrozenItemizer::new(
+            self.item_str_to_id,
+            self.item_id_to_str,
+            self.free_ids,
+            self.normalizer,
+        )
+    }
+
+    /// Freezes the `Itemizer` into a read-only `SortedItemizer`, which looks
+    /// items up by binary search in O(log n) time instead of in a hash map.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// let sorted = itemizer.freeze_sorted();
+    /// assert_eq!(sorted.id_of_opt(&"item1".to_string()), Some(item1));
+    /// ```
+    ///
+    pub fn freeze_sorted(self) -> SortedItemizer<T, I>
+    where
+        T: Ord,
+    {
+        SortedItemizer::new(
+            self.item_str_to_id.into_iter().collect(),
+            self.item_id_to_str,
+            self.normalizer,
+        )
+    }
+
+    /// Returns a `Checkpoint` of the ids assigned so far, which `rollback`
+    /// can return to.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    ///
+    /// let checkpoint = itemizer.checkpoint();
+    /// itemizer.id_of(&"item2".to_string());
+    /// itemizer.rollback(checkpoint);
+    ///
+    /// assert_eq!(itemizer.len(), 1);
+    /// assert!(!itemizer.contains("item2"));
+    /// ```
+    ///
+    pub fn checkpoint(&self) -> Checkpoint<I> {
+        Checkpoint {
+            next_item_id: self.next_item_id,
+            free_ids: self.free_ids.clone(),
+        }
+    }
+
+    /// Removes all items that have been assigned an id since the given
+    /// `Checkpoint` was taken, and frees their ids for reuse.
+    ///
+    /// Items removed since the checkpoint are not restored. Rolling back
+    /// across `clear`, `retain` or `compact` is allowed but not meaningful,
+    /// as these renumber the ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let checkpoint = itemizer.checkpoint();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// itemizer.rollback(checkpoint);
+    /// assert_eq!(itemizer.id_of(&"item2".to_string()), item1);
+    /// ```
+    ///
+    pub fn rollback(&mut self, checkpoint: Checkpoint<I>) {
+        let len = checkpoint
+            .next_item_id
+            .to_usize()
+            .min(self.item_id_to_str.len());
+        let mut free = vec![false; len];
+        for id in &checkpoint.free_ids {
+            if let Some(free) = free.get_mut(id.to_usize()) {
+                *free = true;
+            }
+        }
+
+        self.item_id_to_str.truncate(len);
+        for (slot, free) in self.item_id_to_str.iter_mut().zip(&free) {
+            if *free {
+                *slot = None;
+            }
+        }
+        self.counts.truncate(len);
+        self.item_str_to_id
+            .retain(|_, id| id.as_index() < len && !free[id.as_index()]);
+
+        let removed: Vec<I> = self
+            .free_ids
+            .iter()
+            .copied()
+            .filter(|id| id.to_usize() < len && !free[id.to_usize()])
+            .collect();
+        self.free_ids = checkpoint
+            .free_ids
+            .into_iter()
+            .filter(|id| id.to_usize() < len)
+            .collect();
+        for id in &self.free_ids {
+            if let Some(count) = self.counts.get_mut(id.to_usize()) {
+                *count = 0;
+            }
+        }
+        self.free_ids.extend(removed);
+        self.next_item_id = Item::<I>::at_index(len).id();
+    }
+
+    /// Removes all items with an id of `n` or more, so that the next new
+    /// item is assigned id `n` unless ids below it have been freed by
+    /// `remove`. Does nothing if the `Itemizer` has no more than `n` ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer: Itemizer<&str> = vec!["a", "b", "c"].into_iter().collect();
+    /// itemizer.truncate(1);
+    ///
+    /// assert_eq!(itemizer.len(), 1);
+    /// assert_eq!(itemizer.id_of(&"c").as_index(), 1);
+    /// ```
+    ///
+    pub fn truncate(&mut self, n: usize) {
+        if n >= self.item_id_to_str.len() {
+            return;
+        }
+
+        self.item_id_to_str.truncate(n);
+        self.counts.truncate(n);
+        self.item_str_to_id.retain(|_, id| id.as_index() < n);
+        self.free_ids.retain(|id| id.to_usize() < n);
+        self.next_item_id = Item::<I>::at_index(n).id();
+    }
+
+    /// Splits the `Itemizer` into two at id `k`. The first keeps the items
+    /// with ids `0..k` unchanged, the second holds the items with ids `k..`,
+    /// renumbered to start from 0. Ids freed by `remove` stay free in the
+    /// half they fall into.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is larger than `len()`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let itemizer: Itemizer<&str> = vec!["a", "b", "c"].into_iter().collect();
+    /// let (first, second) = itemizer.split_at(1);
+    ///
+    /// assert_eq!(first.id_of_opt(&"a"), Some(Item::with_id(0)));
+    /// assert_eq!(second.id_of_opt(&"c"), Some(Item::with_id(1)));
+    /// ```
+    ///
+    pub fn split_at(mut self, k: usize) -> (Itemizer<T, I, S>, Itemizer<T, I, S>)
+    where
+        S: Default,
+    {
+        let len = self.item_id_to_str.len();
+        assert!(
+            k <= len,
+            "split index {} is out of bounds for {} ids",
+            k,
+            len
+        );
+
+        let item_id_to_str = self.item_id_to_str.split_off(k);
+        let (free_ids, kept_free_ids) = self.free_ids.iter().partition(|id| id.to_usize() >= k);
+        self.free_ids = kept_free_ids;
+        let free_ids: Vec<I> = free_ids
+            .into_iter()
+            .map(|id| Item::<I>::at_index(id.to_usize() - k).id())
+            .collect();
+
+        // Moves the keys of the second half as they are, which may be
+        // normalized and differ from the values.
+        let keys = mem::replace(
+            &mut self.item_str_to_id,
+            HashMap::with_capacity_and_hasher(k, S::default()),
+        );
+        let mut item_str_to_id = HashMap::with_capacity_and_hasher(len - k, S::default());
+        for (key, id) in keys {
+            if id.as_index() >= k {
+                item_str_to_id.insert(key, Item::at_index(id.as_index() - k));
+            } else {
+                self.item_str_to_id.insert(key, id);
+            }
+        }
+
+        let mut second = Itemizer::from_raw_parts(
+            item_str_to_id,
+            item_id_to_str,
+            free_ids,
+            self.normalizer.clone(),
+        );
+        if self.counts.len() > k {
+            second.counts = self.counts.split_off(k);
+        }
+        second.max_items = self.max_items;
+        self.next_item_id = Item::<I>::at_index(k).id();
+
+        (self, second)
+    }
+
+    /// Keeps only the items for which `f` returns `true` and assigns dense
+    /// ids `0..k` to them, preserving their order.
+    ///
+    /// Returns a table mapping every old id to its new `Item`, or to `None`
+    /// if the item was dropped, which can be used to rewrite previously
+    /// encoded transactions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer: Itemizer<i32> = vec![1, 2, 3].into_iter().collect();
+    /// let remap = itemizer.retain(|value| *value != 2);
+    ///
+    /// assert_eq!(remap, vec![Some(Item::with_id(0)), None, Some(Item::with_id(1))]);
+    /// assert_eq!(itemizer.id_of_opt(&3), Some(Item::with_id(1)));
+    /// ```
+    ///
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, f: F) -> Vec<Option<Item<I>>> {
+        self.renumber(f)
+    }
+
+    /// Drops the ids freed by `remove` and assigns dense ids `0..k` to the
+    /// remaining items, preserving their order.
+    ///
+    /// Returns a table mapping every old id to its new `Item`, or to `None`
+    /// if the id was free, which can be used to rewrite previously encoded
+    /// transactions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer: Itemizer<&str> = vec!["a", "b", "c"].into_iter().collect();
+    /// itemizer.remove(&"a");
+    ///
+    /// let remap = itemizer.compact();
+    ///
+    /// assert_eq!(remap, vec![None, Some(Item::with_id(0)), Some(Item::with_id(1))]);
+    /// assert_eq!(itemizer.len(), 2);
+    /// ```
+    ///
+    pub fn compact(&mut self) -> Vec<Option<Item<I>>> {
+        self.renumber(|_| true)
+    }
+
+    /// Removes the items for which `f` returns `true` and returns them with
+    /// their old ids, in id order.
+    ///
+    /// Like `retain`, this assigns dense ids `0..k` to the remaining items,
+    /// preserving their order, and also returns the table mapping every old
+    /// id to its new `Item`. Drained items and ids that were already free map
+    /// to `None`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer: Itemizer<i32> = vec![1, 2, 3].into_iter().collect();
+    /// let (drained, remap) = itemizer.drain_filter(|value| *value == 2);
+    ///
+    /// assert_eq!(drained, vec![(Item::with_id(1), 2)]);
+    /// assert_eq!(remap, vec![Some(Item::with_id(0)), None, Some(Item::with_id(1))]);
+    /// ```
+    ///
+    #[allow(clippy::type_complexity)]
+    pub fn drain_filter<F: FnMut(&T) -> bool>(
+        &mut self,
+        mut f: F,
+    ) -> (Vec<(Item<I>, T)>, Vec<Option<Item<I>>>) {
+        let mut drained = Vec::new();
+        for (index, slot) in self.item_id_to_str.iter_mut().enumerate() {
+            if let Some(value) = slot.take_if(|value| f(value)) {
+                drained.push((Item::at_index(index), value));
+            }
+        }
+        for (_, value) in &drained {
+            self.item_str_to_id.remove(&*self.key(value));
+        }
+
+        (drained, self.compact())
+    }
+
+    /// Checks that every id in `0..len()` is assigned to an item, i.e. that
+    /// the next id follows the last value and no id has been freed by
+    /// `remove` without being reused since.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ItemizerError::NotDense` with the smallest id without a value
+    /// otherwise. `compact` makes the ids dense again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer: Itemizer<&str> = vec!["a", "b"].into_iter().collect();
+    /// assert!(itemizer.assert_dense().is_ok());
+    ///
+    /// itemizer.remove(&"a");
+    /// assert!(itemizer.assert_dense().is_err());
+    /// ```
+    ///
+    pub fn assert_dense(&self) -> Result<(), ItemizerError> {
+        let len = self.item_id_to_str.len();
+        let next = self.next_item_id.to_usize();
+        if len != next {
+            return Err(ItemizerError::NotDense { id: len.min(next) });
+        }
+        match self.free_ids.iter().min() {
+            Some(id) => Err(ItemizerError::NotDense { id: id.to_usize() }),
+            None => Ok(()),
+        }
+    }
+
+    /// Checks that the internal state of the `Itemizer` is consistent: the
+    /// next id follows the last value, every live value maps back to its own
+    /// id through its normalized key, and the map holds no other entries.
+    ///
+    /// Itemizers built through this crate always pass. This is meant for
+    /// state restored from untrusted sources, and deserialization runs it
+    /// before returning.
+    ///
+    /// A normalizer is a closure and cannot be serialized, so a deserialized
+    /// `Itemizer` has none: its values are keyed as they are, and only their
+    /// exact spelling is found again. Normalize the values before
+    /// serializing if other spellings must keep mapping to them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer: Itemizer<&str> = vec!["a", "b"].into_iter().collect();
+    /// itemizer.remove(&"a");
+    ///
+    /// assert!(itemizer.validate().is_ok());
+    /// ```
+    ///
+    pub fn validate(&self) -> Result<(), ItemizerError> {
+        let len = self.item_id_to_str.len();
+        if self.next_item_id.to_usize() != len {
+            return Err(ItemizerError::UnexpectedId {
+                expected: len,
+                found: self.next_item_id.to_usize(),
+            });
+        }
+
+        let mut values = 0;
+        for (index, value) in self.slots().enumerate() {
+            if let Some(value) = value {
+                match self.item_str_to_id.get(&*self.key(value)) {
+                    Some(id) if id.as_index() == index => values += 1,
+                    Some(_) => return Err(ItemizerError::DuplicateValue { id: index }),
+                    None => return Err(ItemizerError::UnmappedValue { id: index }),
+                }
+            }
+        }
+        if self.item_str_to_id.len() != values {
+            return Err(ItemizerError::LengthMismatch {
+                values,
+                ids: self.item_str_to_id.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Drops freed ids and the items rejected by `keep`, then assigns dense
+    /// ids to the remaining items. Returns the table of old to new ids.
+    fn renumber<F: FnMut(&T) -> bool>(&mut self, mut keep: F) -> Vec<Option<Item<I>>> {
+        let mut next_index = 0;
+        let remap: Vec<Option<Item<I>>> = self
+            .item_id_to_str
+            .iter()
+            .map(|value| match value {
+                Some(value) if keep(value) => {
+                    next_index += 1;
+                    Some(Item::at_index(next_index - 1))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let mut index = 0;
+        self.item_id_to_str.retain(|_| {
+            index += 1;
+            remap[index - 1].is_some()
+        });
+        let mut index = 0;
+        self.counts.retain(|_| {
+            index += 1;
+            remap[index - 1].is_some()
+        });
+        self.item_str_to_id
+            .retain(|_, id| match remap[id.as_index()] {
+                Some(new_id) => {
+                    *id = new_id;
+                    true
+                }
+                None => false,
+            });
+        self.free_ids.clear();
+        self.next_item_id = Item::<I>::at_index(next_index).id();
+
+        remap
+    }
+}
+
+impl<T, I, S> Default for Itemizer<T, I, S>
+where
+    I: ItemId,
+    S: Default,
+{
+    fn default() -> Self {
+        Itemizer::from_raw_parts(HashMap::default(), vec![], vec![], None)
+    }
+}
+
+impl<T, I, S> Index<Item<I>> for Itemizer<T, I, S>
+where
+    I: ItemId,
+{
+    type Output = T;
+
+    /// Returns the value of the given `Item`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Item` is out of range for this `Itemizer` or its id
+    /// has been freed by `remove`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    ///
+    /// assert_eq!(&itemizer[item1], "item1");
+    /// ```
+    ///
+    fn index(&self, item: Item<I>) -> &T {
+        match self.item_id_to_str.get(item.as_index()) {
+            Some(Some(value)) => value,
+            Some(None) => panic!(
+                "item id {} has been removed from the itemizer",
+                item.as_index()
+            ),
+            None => panic!(
+                "item id {} out of range for itemizer of length {}",
+                item.as_index(),
+                self.item_id_to_str.len()
+            ),
+        }
+    }
+}
+
+impl<T, I, S> FromIterator<T> for Itemizer<T, I, S>
+where
+    T: Eq + Hash + Clone,
+    I: ItemId,
+    S: BuildHasher + Default,
+{
+    /// Creates an `Itemizer` assigning ids in iteration order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let itemizer: Itemizer<&str> = vec!["a", "b", "a"].into_iter().collect();
+    ///
+    /// assert_eq!(itemizer.len(), 2);
+    /// assert_eq!(itemizer.id_of_opt(&"b").unwrap().as_index(), 1);
+    /// ```
+    ///
+    fn from_iter<It: IntoIterator<Item = T>>(iter: It) -> Self {
+        let mut itemizer = Itemizer::default();
+        for item in iter {
+            itemizer.id_of(&item);
+        }
+        itemizer
+    }
+}
+
+impl<T, I, S> Extend<T> for Itemizer<T, I, S>
+where
+    T: Eq + Hash + Clone,
+    I: ItemId,
+    S: BuildHasher + Default,
+{
+    /// Assigns ids to all items of the iterator, continuing from the
+    /// current number of items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"a");
+    /// itemizer.extend(vec!["b", "a", "c"]);
+    ///
+    /// assert_eq!(itemizer.len(), 3);
+    /// assert_eq!(itemizer.id_of_opt(&"c").unwrap().as_index(), 2);
+    /// ```
+    ///
+    fn extend<It: IntoIterator<Item = T>>(&mut self, iter: It) {
+        for item in iter {
+            self.id_of(&item);
+        }
+    }
+}
+
+impl<T, I, S> IntoIterator for Itemizer<T, I, S>
+where
+    I: ItemId,
+{
+    type Item = (Item<I>, T);
+    type IntoIter = IntoIter<T, I>;
+
+    /// Consumes the `Itemizer`, returning each `Item` together with its
+    /// value in id order. Ids freed by `remove` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, Itemizer};
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// itemizer.id_of(&"item2".to_string());
+    ///
+    /// let mut iter = itemizer.into_iter();
+    /// assert_eq!(iter.next(), Some((Item::with_id(0), "item1".to_string())));
+    /// assert_eq!(iter.next(), Some((Item::with_id(1), "item2".to_string())));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    ///
+    fn into_iter(self) -> IntoIter<T, I> {
+        let live = self.item_str_to_id.len();
+        IntoIter::new(self.item_id_to_str, live)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, I, H> serde::Serialize for Itemizer<T, I, H>
+where
+    T: serde::Serialize,
+    I: ItemId,
+{
+    /// Serializes the itemizer as the sequence of its values in id order.
+    /// Ids freed by `remove` are serialized as `None`.
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.slots())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, I, S> serde::Deserialize<'de> for Itemizer<T, I, S>
+where
+    T: serde::Deserialize<'de> + Eq + Hash + Clone,
+    I: ItemId,
+    S: BuildHasher + Default,
+{
+    /// Rebuilds the itemizer from a sequence of values in id order. Every
+    /// `None` becomes a freed id, so the next id follows the sequence even
+    /// if all items had been removed.
+    ///
+    /// The result has no normalizer, see `validate`.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let values = Vec::<Option<T>>::deserialize(deserializer)?;
+        if I::from_usize(values.len()).is_none() {
+            return Err(serde::de::Error::custom(
+                "too many values for the item id type",
+            ));
+        }
+
+        let mut item_str_to_id =
+            HashMap::with_capacity_and_hasher(values.len(), Default::default());
+        let mut free_ids = vec![];
+        for (index, value) in values.iter().enumerate() {
+            let id = Item::at_index(index);
+            match value {
+                Some(value) => {
+                    if item_str_to_id.insert(value.clone(), id).is_some() {
+                        return Err(serde::de::Error::custom("duplicate value in itemizer"));
+                    }
+                }
+                None => free_ids.push(id.id()),
+            }
+        }
+
+        let itemizer = Itemizer::from_raw_parts(item_str_to_id, values, free_ids, None);
+        itemizer.validate().map_err(serde::de::Error::custom)?;
+        Ok(itemizer)
+    }
+}
+
+impl<T, I, S> PartialEq for Itemizer<T, I, S>
+where
+    T: PartialEq,
+    I: ItemId,
+{
+    /// Two itemizers are equal if they assign the same ids to the same
+    /// values.
+    fn eq(&self, other: &Self) -> bool {
+        self.item_id_to_str.len() == other.item_id_to_str.len() && self.slots().eq(other.slots())
+    }
+}
+
+impl<T, I, S> Eq for Itemizer<T, I, S>
+where
+    T: Eq,
+    I: ItemId,
+{
+}
+
+impl<T, I, S> Hash for Itemizer<T, I, S>
+where
+    T: Hash,
+    I: ItemId,
+{
+    /// Hashes the values in id order, consistent with `PartialEq`.
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.item_id_to_str.len().hash(state);
+        for slot in self.slots() {
+            slot.hash(state);
+        }
+    }
+}
+
+impl<T, I, S> Debug for Itemizer<T, I, S>
+where
+    T: Debug,
+    I: ItemId,
+{
+    /// Writes one `value: index` line per item, in id order.
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        for (index, value) in self.live() {
+            writeln!(f, "{:?}: {}", value, index)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item::apply_remap;
+    use alloc::string::ToString;
+
+    type FnvHashMap<K, V> = HashMap<K, V, FnvBuildHasher>;
+
+    #[test]
+    fn test_new_itemizer() {
+        let itemizer: Itemizer<String> = Itemizer::new();
+        assert_eq!(itemizer.len(), 0);
+    }
+
+    #[test]
+    fn test_default_itemizer() {
+        let itemizer: Itemizer<String> = Default::default();
+        assert_eq!(itemizer.len(), 0);
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let mut itemizer = Itemizer::with_capacity(100);
+        let mut reference = Itemizer::new();
+        assert_eq!(itemizer.len(), 0);
+
+        for i in 0..100 {
+            let value = format!("item{}", i % 70);
+            assert_eq!(itemizer.id_of(&value), reference.id_of(&value));
+        }
+        assert_eq!(itemizer.len(), 70);
+    }
+
+    #[test]
+    fn test_id_of() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+        let item3 = itemizer.id_of(&"item1".to_string());
+
+        assert_eq!(item1.as_index(), 0);
+        assert_eq!(item2.as_index(), 1);
+        assert_eq!(item3.as_index(), 0);
+        assert_eq!(itemizer.len(), 2);
+    }
+
+    #[test]
+    fn test_id_of_opt() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of_opt(&"item2".to_string());
+        let item3 = itemizer.id_of_opt(&"item1".to_string());
+
+        assert_eq!(item1.as_index(), 0);
+        assert_eq!(item2, None);
+        assert_eq!(item3, Some(Item::with_id(0)));
+    }
+
+    #[test]
+    fn test_contains() {
+        let mut itemizer = Itemizer::new();
+        assert!(!itemizer.contains(&"item1".to_string()));
+        itemizer.id_of(&"item1".to_string());
+        assert!(itemizer.contains(&"item1".to_string()));
+        assert!(!itemizer.contains(&"item2".to_string()));
+        assert_eq!(itemizer.len(), 1);
+    }
+
+    #[test]
+    fn test_id_of_required() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        assert_eq!(
+            itemizer.id_of_required(&"item1".to_string()).unwrap(),
+            item1
+        );
+
+        let err = itemizer.id_of_required(&"missing".to_string()).unwrap_err();
+        assert!(matches!(&err, ItemizerError::UnknownItem(item) if item == "\"missing\""));
+        assert!(err.to_string().contains("missing"));
+    }
+
+    #[test]
+    fn test_from_iter() {
+        let values = vec![
+            "item1".to_string(),
+            "item2".to_string(),
+            "item1".to_string(),
+            "item3".to_string(),
+        ];
+        let itemizer: Itemizer<String> = values.into_iter().collect();
+
+        assert_eq!(itemizer.len(), 3);
+        assert_eq!(
+            itemizer.id_of_opt(&"item1".to_string()),
+            Some(Item::with_id(0))
+        );
+        assert_eq!(
+            itemizer.id_of_opt(&"item2".to_string()),
+            Some(Item::with_id(1))
+        );
+        assert_eq!(
+            itemizer.id_of_opt(&"item3".to_string()),
+            Some(Item::with_id(2))
+        );
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        itemizer.extend(vec![
+            "item3".to_string(),
+            "item1".to_string(),
+            "item4".to_string(),
+        ]);
+
+        assert_eq!(itemizer.len(), 4);
+        assert_eq!(itemizer.id_of_opt(&"item1".to_string()), Some(item1));
+        assert_eq!(itemizer.id_of_opt(&"item2".to_string()), Some(item2));
+        assert_eq!(
+            itemizer.id_of_opt(&"item3".to_string()),
+            Some(Item::with_id(2))
+        );
+        assert_eq!(
+            itemizer.id_of_opt(&"item4".to_string()),
+            Some(Item::with_id(3))
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+        let item3 = itemizer.id_of(&"item3".to_string());
+
+        assert_eq!(itemizer.remove(&"item2".to_string()), Some(item2));
+        assert_eq!(itemizer.remove(&"item2".to_string()), None);
+        assert!(!itemizer.contains(&"item2".to_string()));
+
+        assert_eq!(itemizer.value_of(&item1), &"item1".to_string());
+        assert_eq!(itemizer.value_of(&item3), &"item3".to_string());
+        assert_eq!(itemizer.id_of_opt(&"item3".to_string()), Some(item3));
+
+        let item4 = itemizer.id_of(&"item4".to_string());
+        assert_eq!(item4, item2);
+        assert_eq!(itemizer.value_of(&item4), &"item4".to_string());
+        assert_eq!(itemizer.len(), 3);
+
+        let item5 = itemizer.id_of(&"item5".to_string());
+        assert_eq!(item5.as_index(), 3);
+    }
+
+    #[test]
+    fn test_removed_ids_are_skipped() {
+        let mut itemizer: Itemizer<&str> = vec!["a", "b", "c", "d"].into_iter().collect();
+        let b = itemizer.remove(&"b").unwrap();
+        itemizer.remove(&"d");
+
+        assert_eq!(itemizer.len(), 4);
+        assert_eq!(itemizer.value_of_opt(&b), None);
+        assert_eq!(itemizer.values(), &[Some("a"), None, Some("c"), None]);
+        assert_eq!(itemizer.iter().len(), 2);
+        assert_eq!(itemizer.iter().rev().collect::<Vec<_>>(), vec![&"c", &"a"]);
+        assert_eq!(
+            itemizer
+                .iter_ids()
+                .map(|id| id.as_index())
+                .collect::<Vec<_>>(),
+            vec![0, 2]
+        );
+        assert_eq!(
+            itemizer.iter_items().map(|(id, _)| id).collect::<Vec<_>>(),
+            itemizer.iter_ids().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            itemizer.enumerate().collect::<Vec<_>>(),
+            vec![(0, &"a"), (2, &"c")]
+        );
+        assert_eq!(itemizer.to_id_value_map().len(), 2);
+        assert_eq!(format!("{:?}", itemizer), "\"a\": 0\n\"c\": 2\n");
+
+        let mut into_iter = itemizer.clone().into_iter();
+        assert_eq!(into_iter.len(), 2);
+        assert_eq!(into_iter.next_back(), Some((Item::with_id(2), "c")));
+        assert_eq!(into_iter.next(), Some((Item::with_id(0), "a")));
+        assert_eq!(into_iter.next(), None);
+
+        assert_eq!(itemizer.id_of(&"e"), Item::with_id(3));
+        assert_eq!(itemizer.iter().collect::<Vec<_>>(), vec![&"a", &"c", &"e"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "item id 1 has been removed")]
+    fn test_value_of_removed_id_panics() {
+        let mut itemizer: Itemizer<&str> = vec!["a", "b"].into_iter().collect();
+        let b = itemizer.remove(&"b").unwrap();
+        itemizer.value_of(&b);
+    }
+
+    #[test]
+    fn test_value_of() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        assert_eq!(itemizer.value_of(&item1), &"item1".to_string());
+        assert_eq!(itemizer.value_of(&item2), &"item2".to_string());
+    }
+
+    #[test]
+    fn test_len() {
+        let mut itemizer = Itemizer::new();
+        assert_eq!(itemizer.len(), 0);
+        itemizer.id_of(&"item1".to_string());
+        assert_eq!(itemizer.len(), 1);
+        itemizer.id_of(&"item2".to_string());
+        assert_eq!(itemizer.len(), 2);
+        itemizer.id_of(&"item1".to_string());
+        assert_eq!(itemizer.len(), 2);
+    }
+
+    #[test]
+    fn test_iter() {
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&"item1".to_string());
+        itemizer.id_of(&"item2".to_string());
+
+        let mut iter = itemizer.iter();
+        assert_eq!(iter.next(), Some(&"item1".to_string()));
+        assert_eq!(iter.next(), Some(&"item2".to_string()));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_iteration_order_is_first_seen_order() {
+        let mut itemizer = Itemizer::new();
+        let mut first_seen = vec![];
+        for i in 0..500u32 {
+            // Mix new items with repeats of earlier ones.
+            let value = format!("item{}", (i * 7919) % 300);
+            if !itemizer.contains(&value) {
+                first_seen.push(value.clone());
+            }
+            itemizer.id_of(&value);
+        }
+        assert_eq!(itemizer.len(), first_seen.len());
+
+        assert!(itemizer.iter().eq(first_seen.iter()));
+        assert!(itemizer
+            .iter_items()
+            .map(|(item, value)| (item.as_index(), value))
+            .eq(first_seen.iter().enumerate()));
+        assert!(itemizer.enumerate().eq(first_seen.iter().enumerate()));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_round_trip() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        let json = serde_json::to_string(&itemizer).unwrap();
+        assert_eq!(json, r#"["item1","item2"]"#);
+
+        let mut restored: Itemizer<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.value_of(&item1), &"item1".to_string());
+        assert_eq!(restored.value_of(&item2), &"item2".to_string());
+        assert_eq!(restored.id_of_opt(&"item2".to_string()), Some(item2));
+        assert_eq!(restored.id_of(&"item3".to_string()).as_index(), 2);
+
+        let json = serde_json::to_string(&item2).unwrap();
+        assert_eq!(json, "1");
+        assert_eq!(serde_json::from_str::<Item>(&json).unwrap(), item2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_removed_items() {
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+        itemizer.remove(&"item1".to_string());
+        itemizer.remove(&"item2".to_string());
+        let item1 = itemizer.id_of(&"item1".to_string());
+        assert_eq!(item1, item2);
+
+        let json = serde_json::to_string(&itemizer).unwrap();
+        assert_eq!(json, r#"[null,"item1"]"#);
+
+        let mut restored: Itemizer<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.id_of_opt(&"item1".to_string()), Some(item1));
+        assert_eq!(restored.id_of(&"item3".to_string()).as_index(), 0);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_all_items_removed() {
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        let ids = itemizer.ids_of(&["item1".to_string(), "item2".to_string()]);
+        itemizer.remove(&"item1".to_string());
+        itemizer.remove(&"item2".to_string());
+
+        let json = serde_json::to_string(&itemizer).unwrap();
+        assert_eq!(json, "[null,null]");
+
+        let mut restored: Itemizer<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, itemizer);
+        assert_eq!(restored.len(), 2);
+        assert_eq!(restored.next_id(), itemizer.next_id());
+        assert_eq!(restored.id_of(&"item3".to_string()), ids[1]);
+        assert_eq!(restored.id_of(&"item4".to_string()), ids[0]);
+        assert_eq!(restored.id_of(&"item5".to_string()).as_index(), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_drops_normalizer() {
+        let mut itemizer = Itemizer::new_case_insensitive();
+        let milk = itemizer.id_of(&"Milk".to_string());
+
+        let json = serde_json::to_string(&itemizer).unwrap();
+        let restored: Itemizer<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.id_of_opt("Milk"), Some(milk));
+        assert_eq!(restored.id_of_opt("milk"), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_rejects_duplicates() {
+        let result = serde_json::from_str::<Itemizer<String>>(r#"["a","b","a"]"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_iter_items() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        let mut iter = itemizer.iter_items();
+        assert_eq!(iter.next(), Some((item1, &"item1".to_string())));
+        assert_eq!(iter.next(), Some((item2, &"item2".to_string())));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&"item1".to_string());
+        itemizer.id_of(&"item2".to_string());
+        itemizer.id_of(&"item1".to_string());
+        itemizer.id_of(&"item3".to_string());
+
+        let pairs: Vec<(Item, String)> = itemizer.into_iter().collect();
+        assert_eq!(
+            pairs,
+            vec![
+                (Item::with_id(0), "item1".to_string()),
+                (Item::with_id(1), "item2".to_string()),
+                (Item::with_id(2), "item3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&"item1".to_string());
+        itemizer.id_of(&"item2".to_string());
+        itemizer.remove(&"item1".to_string());
+
+        itemizer.clear();
+        assert_eq!(itemizer.len(), 0);
+        assert!(!itemizer.contains(&"item2".to_string()));
+
+        let item3 = itemizer.id_of(&"item3".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+        assert_eq!(item3.as_index(), 0);
+        assert_eq!(item2.as_index(), 1);
+        assert_eq!(itemizer.value_of(&item3), &"item3".to_string());
+        assert_eq!(itemizer.len(), 2);
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut itemizer = Itemizer::with_capacity(1000);
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        itemizer.shrink_to_fit();
+
+        assert_eq!(itemizer.len(), 2);
+        assert_eq!(itemizer.value_of(&item1), &"item1".to_string());
+        assert_eq!(itemizer.value_of(&item2), &"item2".to_string());
+        assert_eq!(itemizer.id_of_opt(&"item2".to_string()), Some(item2));
+    }
+
+    #[test]
+    fn test_ids_of() {
+        let transaction = vec![
+            "item1".to_string(),
+            "item2".to_string(),
+            "item1".to_string(),
+            "item3".to_string(),
+        ];
+
+        let mut itemizer = Itemizer::new();
+        let items = itemizer.ids_of(&transaction);
+
+        let mut reference = Itemizer::new();
+        let expected: Vec<Item> = transaction.iter().map(|t| reference.id_of(t)).collect();
+
+        assert_eq!(items, expected);
+        assert_eq!(itemizer.len(), 3);
+    }
+
+    #[test]
+    fn test_values() {
+        let mut itemizer = Itemizer::new();
+        let items = itemizer.ids_of(&[3, 1, 4, 1, 5]);
+
+        assert_eq!(itemizer.values(), &[3, 1, 4, 5].map(Some));
+        for item in items {
+            assert_eq!(
+                itemizer.values()[item.as_index()].as_ref(),
+                Some(itemizer.value_of(&item))
+            );
+        }
+    }
+
+    #[test]
+    fn test_index() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        assert_eq!(itemizer[item1], "item1".to_string());
+        assert_eq!(itemizer[item2], "item2".to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "item id 5 out of range for itemizer of length 1")]
+    fn test_index_out_of_range() {
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&"item1".to_string());
+
+        let _ = &itemizer[Item::with_id(5)];
+    }
+
+    #[test]
+    fn test_value_of_opt() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        assert_eq!(itemizer.value_of_opt(&item1), Some(&"item1".to_string()));
+        assert_eq!(itemizer.value_of_opt(&Item::with_id(999)), None);
+
+        itemizer.remove(&"item2".to_string());
+        assert_eq!(itemizer.value_of_opt(&item2), None);
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut itemizer: Itemizer<i32> = Itemizer::new();
+        itemizer.ids_of(&[30, 10, 20, 10]);
+        assert_eq!(format!("{:?}", itemizer), "30: 0\n10: 1\n20: 2\n");
+
+        itemizer.remove(&10);
+        assert_eq!(format!("{:?}", itemizer), "30: 0\n20: 2\n");
+
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&"item1".to_string());
+        assert_eq!(format!("{:?}", itemizer), "\"item1\": 0\n");
+    }
+
+    #[test]
+    fn test_try_id_of_exhausted() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        itemizer.next_item_id = u32::MAX;
+
+        assert_eq!(itemizer.try_id_of(&"item1".to_string()).unwrap(), item1);
+        assert!(matches!(
+            itemizer.try_id_of(&"item2".to_string()),
+            Err(ItemizerError::IdSpaceExhausted)
+        ));
+        assert!(!itemizer.contains(&"item2".to_string()));
+        assert_eq!(itemizer.len(), 1);
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut itemizer: Itemizer<String> = ["milk", "bread", "eggs"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut other: Itemizer<String> = Itemizer::new();
+        let encoded = other.ids_of(&[
+            "butter".to_string(),
+            "milk".to_string(),
+            "jam".to_string(),
+            "milk".to_string(),
+        ]);
+
+        let remap = itemizer.merge(&other);
+        assert_eq!(
+            remap,
+            vec![
+                Some(Item::with_id(3)),
+                Some(Item::with_id(0)),
+                Some(Item::with_id(4))
+            ]
+        );
+        assert_eq!(itemizer.len(), 5);
+
+        let rewritten: Vec<&String> = apply_remap(&encoded, &remap)
+            .iter()
+            .map(|item| itemizer.value_of(item))
+            .collect();
+        assert_eq!(rewritten, vec!["butter", "milk", "jam", "milk"]);
+    }
+
+    #[test]
+    fn test_merge_with_free_ids() {
+        let mut itemizer: Itemizer<&str> = vec!["a"].into_iter().collect();
+        let mut other: Itemizer<&str> = vec!["b", "c", "a"].into_iter().collect();
+        other.remove(&"c");
+
+        let remap = itemizer.merge(&other);
+        assert_eq!(
+            remap,
+            vec![Some(Item::with_id(1)), None, Some(Item::with_id(0))]
+        );
+        assert_eq!(itemizer.len(), 2);
+        assert!(!itemizer.contains(&"c"));
+    }
+
+    #[test]
+    fn test_id_of_owned() {
+        let padding = "x".repeat(1000);
+        let mut itemizer = Itemizer::new();
+
+        for i in 0..10_000 {
+            let item = itemizer.id_of_owned(format!("{}{}", padding, i));
+            assert_eq!(item.as_index(), i);
+        }
+        for i in (0..10_000).step_by(7) {
+            let item = itemizer.id_of_owned(format!("{}{}", padding, i));
+            assert_eq!(item.as_index(), i);
+        }
+
+        assert_eq!(itemizer.len(), 10_000);
+        assert_eq!(
+            itemizer.value_of(&Item::with_id(42)),
+            &format!("{}{}", padding, 42)
+        );
+    }
+
+    #[test]
+    fn test_u16_ids() {
+        let mut itemizer: Itemizer<String, u16> = Itemizer::default();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        assert_eq!(item1.id(), 0u16);
+        assert_eq!(item2.as_index(), 1);
+        assert_eq!(Item::<u16>::from_index(item2.as_index()), Some(item2));
+        assert_eq!(itemizer.value_of(&item2), &"item2".to_string());
+        assert_eq!(size_of::<Item<u16>>(), 2);
+
+        let collected: Itemizer<i32, u64> = vec![5, 6, 5].into_iter().collect();
+        assert_eq!(collected.id_of_opt(&6).map(|item| item.id()), Some(1u64));
+    }
+
+    #[test]
+    fn test_u16_ids_exhausted() {
+        let mut itemizer: Itemizer<u32, u16> = Itemizer::default();
+        for value in 0..u16::MAX as u32 {
+            assert_eq!(itemizer.id_of(&value).as_index(), value as usize);
+        }
+
+        assert!(matches!(
+            itemizer.try_id_of(&(u16::MAX as u32)),
+            Err(ItemizerError::IdSpaceExhausted)
+        ));
+        assert_eq!(itemizer.try_id_of(&7).unwrap().as_index(), 7);
+    }
+
+    #[test]
+    fn test_eq() {
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&"item1".to_string());
+        itemizer.id_of(&"item2".to_string());
+        itemizer.id_of(&"item1".to_string());
+
+        let collected: Itemizer<String> = vec!["item1".to_string(), "item2".to_string()]
+            .into_iter()
+            .collect();
+        assert_eq!(itemizer, collected);
+
+        let reversed: Itemizer<String> = vec!["item2".to_string(), "item1".to_string()]
+            .into_iter()
+            .collect();
+        assert_ne!(itemizer, reversed);
+
+        let mut removed: Itemizer<String> = vec!["item1".to_string(), "item2".to_string()]
+            .into_iter()
+            .collect();
+        removed.remove(&"item2".to_string());
+        assert_ne!(itemizer, removed);
+    }
+
+    #[test]
+    fn test_reserve() {
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&0);
+        itemizer.reserve(1000);
+
+        for value in 0..1000 {
+            assert_eq!(itemizer.id_of(&value).as_index(), value as usize);
+        }
+        assert_eq!(itemizer.len(), 1000);
+    }
+
+    #[test]
+    fn test_decode() {
+        let transaction = vec!["milk".to_string(), "bread".to_string(), "milk".to_string()];
+        let mut itemizer = Itemizer::new();
+        let items = itemizer.ids_of(&transaction);
+
+        let decoded: Vec<String> = itemizer.decode(&items).into_iter().cloned().collect();
+        assert_eq!(decoded, transaction);
+
+        let mut with_unknown = items.clone();
+        with_unknown.push(Item::with_id(10));
+        assert_eq!(
+            itemizer.decode_opt(&with_unknown),
+            vec![
+                Some(&transaction[0]),
+                Some(&transaction[1]),
+                Some(&transaction[2]),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_or() {
+        let mut itemizer = Itemizer::new();
+        let items = itemizer.ids_of(&["milk", "bread", "eggs"].map(String::from));
+        itemizer.remove(&"eggs".to_string());
+
+        let unknown = "<unknown>".to_string();
+        let transaction = vec![items[1], Item::with_id(42), items[0], items[2]];
+        assert_eq!(
+            itemizer.decode_or(&transaction, &unknown),
+            vec!["bread", "<unknown>", "milk", "<unknown>"]
+        );
+    }
+
+    #[test]
+    fn test_count_of() {
+        let mut itemizer = Itemizer::new();
+        for value in ["a", "b", "a", "c", "a", "b"] {
+            itemizer.count_of(&value);
+        }
+        let d = itemizer.id_of(&"d");
+
+        assert_eq!(itemizer.count(&itemizer.id_of_opt(&"a").unwrap()), 3);
+        assert_eq!(itemizer.count(&itemizer.id_of_opt(&"b").unwrap()), 2);
+        assert_eq!(itemizer.count(&itemizer.id_of_opt(&"c").unwrap()), 1);
+        assert_eq!(itemizer.count(&d), 0);
+
+        let b = itemizer.remove(&"b").unwrap();
+        assert_eq!(itemizer.count(&b), 0);
+        assert_eq!(itemizer.count_of(&"e"), b);
+        assert_eq!(itemizer.count(&b), 1);
+    }
+
+    #[test]
+    fn test_most_frequent() {
+        let mut itemizer = Itemizer::new();
+        assert_eq!(itemizer.most_frequent(), None);
+        itemizer.id_of(&"a");
+        assert_eq!(itemizer.most_frequent(), None);
+
+        for value in ["b", "c", "c", "b", "c", "d", "c", "a"] {
+            itemizer.count_of(&value);
+        }
+        let c = itemizer.id_of_opt(&"c").unwrap();
+        assert_eq!(itemizer.most_frequent(), Some((c, 4)));
+
+        itemizer.remove(&"c");
+        let b = itemizer.id_of_opt(&"b").unwrap();
+        assert_eq!(itemizer.most_frequent(), Some((b, 2)));
+
+        itemizer.count_of(&"a");
+        let a = itemizer.id_of_opt(&"a").unwrap();
+        assert_eq!(itemizer.most_frequent(), Some((a, 2)));
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut itemizer: Itemizer<i32> = Itemizer::new();
+        let encoded = itemizer.ids_of(&[5, 2, 8, 3, 4, 2]);
+        itemizer.count_of(&8);
+
+        let remap = itemizer.retain(|value| value % 2 == 0);
+
+        assert_eq!(
+            remap,
+            vec![
+                None,
+                Some(Item::with_id(0)),
+                Some(Item::with_id(1)),
+                None,
+                Some(Item::with_id(2))
+            ]
+        );
+        assert_eq!(itemizer.len(), 3);
+        assert_eq!(itemizer.values(), &[2, 8, 4].map(Some));
+        assert_eq!(itemizer.id_of_opt(&4), Some(Item::with_id(2)));
+        assert_eq!(itemizer.id_of_opt(&5), None);
+        assert_eq!(itemizer.count(&Item::with_id(1)), 1);
+
+        let rewritten: Vec<Item> = encoded
+            .iter()
+            .filter_map(|item| remap[item.as_index()])
+            .collect();
+        assert_eq!(itemizer.decode(&rewritten), vec![&2, &8, &4, &2]);
+
+        assert_eq!(itemizer.id_of(&7), Item::with_id(3));
+    }
+
+    #[test]
+    fn test_retain_removed() {
+        let mut itemizer: Itemizer<i32> = vec![1, 2, 3].into_iter().collect();
+        itemizer.remove(&1);
+
+        let remap = itemizer.retain(|_| true);
+
+        assert_eq!(
+            remap,
+            vec![None, Some(Item::with_id(0)), Some(Item::with_id(1))]
+        );
+        assert_eq!(itemizer.values(), &[2, 3].map(Some));
+        assert_eq!(itemizer.id_of(&1), Item::with_id(2));
+    }
+
+    #[test]
+    fn test_clone() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+
+        let mut snapshot = itemizer.clone();
+        let item2 = snapshot.id_of(&"item2".to_string());
+        snapshot.remove(&"item1".to_string());
+
+        assert_eq!(itemizer.len(), 1);
+        assert_eq!(itemizer.id_of_opt(&"item1".to_string()), Some(item1));
+        assert_eq!(itemizer.id_of_opt(&"item2".to_string()), None);
+        assert_eq!(snapshot.id_of_opt(&"item2".to_string()), Some(item2));
+
+        assert_eq!(itemizer.id_of(&"item3".to_string()), item2);
+    }
+
+    #[test]
+    fn test_from_values() {
+        let values = vec!["milk".to_string(), "bread".to_string(), "eggs".to_string()];
+        let mut itemizer = Itemizer::from_values(values);
+
+        assert_eq!(itemizer.len(), 3);
+        assert_eq!(
+            itemizer.id_of_opt(&"milk".to_string()),
+            Some(Item::with_id(0))
+        );
+        assert_eq!(
+            itemizer.id_of_opt(&"eggs".to_string()),
+            Some(Item::with_id(2))
+        );
+        assert_eq!(itemizer.value_of(&Item::with_id(1)), "bread");
+        assert_eq!(itemizer.id_of(&"jam".to_string()), Item::with_id(3));
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate value at index 2")]
+    fn test_from_values_duplicates() {
+        Itemizer::from_values(vec![1, 2, 1]);
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let mut itemizer = Itemizer::new_case_insensitive();
+        let milk = itemizer.id_of(&"Milk".to_string());
+        let bread = itemizer.id_of(&"bread".to_string());
+
+        assert_eq!(itemizer.id_of(&"MILK".to_string()), milk);
+        assert_eq!(itemizer.id_of_owned("milk".to_string()), milk);
+        assert_eq!(itemizer.id_of_opt(&"BREAD".to_string()), Some(bread));
+        assert!(itemizer.contains(&"mIlK".to_string()));
+        assert_eq!(itemizer.len(), 2);
+        assert_eq!(itemizer.value_of(&milk), "Milk");
+
+        assert_eq!(itemizer.remove(&"Bread".to_string()), Some(bread));
+        assert_eq!(itemizer.id_of(&"EGGS".to_string()), bread);
+        assert_eq!(itemizer.id_of_opt(&"eggs".to_string()), Some(bread));
+
+        let frozen = itemizer.freeze();
+        assert_eq!(frozen.id_of_opt(&"milk".to_string()), Some(milk));
+    }
+
+    #[test]
+    fn test_with_normalizer() {
+        let mut itemizer = Itemizer::with_normalizer(|item: &String| item.trim().to_string());
+        let a = itemizer.id_of(&"a ".to_string());
+
+        assert_eq!(itemizer.id_of(&"a".to_string()), a);
+        assert_eq!(itemizer.id_of(&"  a".to_string()), a);
+        assert_eq!(itemizer.id_of_opt(&"a\t".to_string()), Some(a));
+        assert_eq!(itemizer.id_of_opt(&"b".to_string()), None);
+        assert_eq!(itemizer.value_of(&a), "a ");
+        assert_eq!(itemizer.len(), 1);
+
+        let mut rounded: Itemizer<i32> = Itemizer::with_normalizer(|value: &i32| value / 10);
+        let tens = rounded.ids_of(&[12, 17, 25, 10]);
+        assert_eq!(tens[0], tens[1]);
+        assert_eq!(tens[0], tens[3]);
+        assert_ne!(tens[0], tens[2]);
+        assert_eq!(rounded.values(), &[12, 25].map(Some));
+    }
+
+    #[test]
+    fn test_ids_of_opt() {
+        let mut itemizer = Itemizer::new();
+        let milk = itemizer.id_of(&"milk".to_string());
+        let eggs = itemizer.id_of(&"eggs".to_string());
+
+        let transaction = vec![
+            "milk".to_string(),
+            "jam".to_string(),
+            "eggs".to_string(),
+            "tea".to_string(),
+        ];
+        assert_eq!(
+            itemizer.ids_of_opt(&transaction),
+            vec![Some(milk), None, Some(eggs), None]
+        );
+        assert_eq!(itemizer.len(), 2);
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let mut itemizer = Itemizer::new();
+        assert!(itemizer.is_empty());
+        itemizer.id_of(&"item1".to_string());
+        assert!(!itemizer.is_empty());
+        itemizer.clear();
+        assert!(itemizer.is_empty());
+    }
+
+    #[test]
+    fn test_id_of_entry() {
+        let mut itemizer = Itemizer::new();
+        let (item1, inserted) = itemizer.id_of_entry(&"item1".to_string());
+        assert!(inserted);
+        assert_eq!(item1.as_index(), 0);
+
+        assert_eq!(itemizer.id_of_entry(&"item1".to_string()), (item1, false));
+        assert_eq!(
+            itemizer.id_of_entry(&"item2".to_string()),
+            (Item::with_id(1), true)
+        );
+        assert_eq!(itemizer.len(), 2);
+    }
+
+    #[test]
+    fn test_borrowed_lookup() {
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        let milk = itemizer.id_of(&"milk".to_string());
+
+        let token: &str = "milk";
+        assert_eq!(itemizer.id_of_opt(token), Some(milk));
+        assert!(itemizer.contains(token));
+        assert_eq!(itemizer.id_of_opt("bread"), None);
+        assert!(!itemizer.contains("bread"));
+
+        let mut itemizer = Itemizer::new_case_insensitive();
+        let milk = itemizer.id_of(&"Milk".to_string());
+        assert_eq!(itemizer.id_of_opt("MILK"), Some(milk));
+        assert_eq!(itemizer.freeze().id_of_opt("milk"), Some(milk));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_with_hasher() {
+        use std::collections::hash_map::RandomState;
+
+        let mut itemizer: Itemizer<String, u32, RandomState> =
+            Itemizer::with_hasher(RandomState::new());
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        assert_eq!(item1.as_index(), 0);
+        assert_eq!(item2.as_index(), 1);
+        assert_eq!(itemizer.id_of(&"item1".to_string()), item1);
+        assert_eq!(itemizer.id_of_opt("item2"), Some(item2));
+        assert_eq!(itemizer.value_of(&item2), "item2");
+
+        itemizer.remove(&"item1".to_string());
+        assert!(!itemizer.contains("item1"));
+        assert_eq!(itemizer.id_of(&"item3".to_string()), item1);
+
+        let frozen = itemizer.clone().freeze();
+        assert_eq!(frozen.id_of_opt("item3"), Some(item1));
+        assert_eq!(frozen.thaw(), itemizer);
+    }
+
+    #[test]
+    fn test_next_id_after_remove() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        itemizer.id_of(&"item2".to_string());
+        assert_eq!(itemizer.next_id(), Item::with_id(2));
+
+        itemizer.remove(&"item1".to_string());
+        assert_eq!(itemizer.next_id(), item1);
+        assert_eq!(itemizer.id_of(&"item3".to_string()), item1);
+        assert_eq!(itemizer.next_id(), Item::with_id(2));
+    }
+
+    #[test]
+    fn test_would_assign() {
+        let mut itemizer = Itemizer::new_case_insensitive();
+        let milk = itemizer.id_of(&"milk".to_string());
+        itemizer.id_of(&"bread".to_string());
+
+        assert_eq!(itemizer.would_assign(&"milk".to_string()), (milk, false));
+        assert_eq!(itemizer.would_assign(&"MILK".to_string()), (milk, false));
+        assert_eq!(
+            itemizer.would_assign(&"eggs".to_string()),
+            (Item::with_id(2), true)
+        );
+
+        itemizer.remove(&"milk".to_string());
+        let (id, new) = itemizer.would_assign(&"eggs".to_string());
+        assert_eq!((id, new), (itemizer.next_id(), true));
+        assert_eq!(itemizer.id_of(&"eggs".to_string()), id);
+        assert_eq!(itemizer.would_assign(&"eggs".to_string()), (id, false));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let mut itemizer1 = Itemizer::new();
+        itemizer1.id_of(&"item1".to_string());
+        itemizer1.id_of(&"item2".to_string());
+        let itemizer2: Itemizer<String> = vec!["item1".to_string(), "item2".to_string()]
+            .into_iter()
+            .collect();
+        let itemizer3: Itemizer<String> = vec!["item2".to_string(), "item1".to_string()]
+            .into_iter()
+            .collect();
+
+        let mut set = HashSet::new();
+        set.insert(itemizer1);
+        set.insert(itemizer2);
+        assert_eq!(set.len(), 1);
+
+        set.insert(itemizer3);
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_itemize_csr() {
+        let mut itemizer = Itemizer::new();
+        let (ids, offsets) = itemizer.itemize_csr(vec![
+            vec!["milk", "bread"],
+            vec![],
+            vec!["bread", "eggs", "milk"],
+        ]);
+
+        assert_eq!(offsets, vec![0, 2, 2, 5]);
+        assert_eq!(ids, vec![0, 1, 1, 2, 0]);
+        assert_eq!(
+            &ids[offsets[2]..offsets[3]],
+            &[
+                itemizer.id_of(&"bread").id(),
+                itemizer.id_of(&"eggs").id(),
+                itemizer.id_of(&"milk").id()
+            ]
+        );
+        assert_eq!(itemizer.len(), 3);
+    }
+
+    #[test]
+    fn test_to_id_value_map() {
         let mut itemizer = Itemizer::new();
-        assert_eq!(itemizer.len(), 0);
-        itemizer.id_of(&"item1".to_string());
-        assert_eq!(itemizer.len(), 1);
-        itemizer.id_of(&"item2".to_string());
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+        let item3 = itemizer.id_of(&"item3".to_string());
+        itemizer.remove(&"item2".to_string());
+
+        let map = itemizer.to_id_value_map();
+        assert_eq!(map.len(), 2);
+        assert_eq!(&map[&item1], itemizer.value_of(&item1));
+        assert_eq!(&map[&item3], itemizer.value_of(&item3));
+        assert_eq!(map.get(&item2), None);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_to_id_value_map_is_fnv_hash_map() {
+        let itemizer: Itemizer<&str> = vec!["milk", "jam"].into_iter().collect();
+
+        let map: fnv::FnvHashMap<Item, &str> = itemizer.to_id_value_map();
+        assert_eq!(map[&Item::with_id(1)], "jam");
+    }
+
+    #[test]
+    fn test_value_of_many() {
+        let mut itemizer = Itemizer::new();
+        let items = itemizer.ids_of(&["milk".to_string(), "bread".to_string()]);
+
+        assert_eq!(
+            itemizer.value_of_many(&[items[1], items[0]]),
+            Some(vec![&"bread".to_string(), &"milk".to_string()])
+        );
+        assert_eq!(itemizer.value_of_many(&[]), Some(vec![]));
+        assert_eq!(itemizer.value_of_many(&[items[0], Item::with_id(2)]), None);
+    }
+
+    #[test]
+    fn test_capacity() {
+        let mut itemizer = Itemizer::new();
+        for i in 0..10 {
+            itemizer.id_of(&i);
+            assert!(itemizer.capacity() >= itemizer.len());
+        }
+
+        itemizer.reserve(100);
+        assert!(itemizer.capacity() >= 110);
+        itemizer.shrink_to_fit();
+        assert!(itemizer.capacity() >= itemizer.len());
+    }
+
+    #[test]
+    fn test_insert_at() {
+        let mut itemizer = Itemizer::new();
+        let item5 = itemizer.insert_at(5, "e".to_string()).unwrap();
+        let item2 = itemizer.insert_at(2, "b".to_string()).unwrap();
+
+        assert_eq!(item5, Item::with_id(5));
+        assert_eq!(item2, Item::with_id(2));
+        assert_eq!(itemizer.len(), 6);
+        assert_eq!(itemizer.value_of(&item5), "e");
+        assert_eq!(itemizer.value_of(&item2), "b");
+        assert_eq!(itemizer.id_of_opt("e"), Some(item5));
+        assert_eq!(itemizer.id_of_opt("b"), Some(item2));
+        assert_eq!(itemizer.value_of_opt(&Item::with_id(3)), None);
+        assert_eq!(itemizer.iter().collect::<Vec<_>>(), vec!["b", "e"]);
+        assert_eq!(
+            itemizer.clone().into_values(),
+            vec![
+                None,
+                None,
+                Some("b".to_string()),
+                None,
+                None,
+                Some("e".to_string())
+            ]
+        );
+        assert!(itemizer.validate().is_ok());
+
+        assert_eq!(itemizer.insert_at(5, "e".to_string()).unwrap(), item5);
+        assert!(matches!(
+            itemizer.insert_at(5, "f".to_string()),
+            Err(ItemizerError::IdOccupied { id: 5 })
+        ));
+        assert!(matches!(
+            itemizer.insert_at(1, "e".to_string()),
+            Err(ItemizerError::DuplicateValue { id: 1 })
+        ));
+
+        let mut reused: Vec<usize> = (0..4)
+            .map(|i| itemizer.id_of(&i.to_string()).as_index())
+            .collect();
+        reused.sort();
+        assert_eq!(reused, vec![0, 1, 3, 4]);
+        assert_eq!(itemizer.id_of(&"g".to_string()), Item::with_id(6));
+    }
+
+    #[test]
+    fn test_compact() {
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        let items = itemizer.ids_of(&["a", "b", "c", "d", "e"].map(String::from));
+        itemizer.remove(&"b".to_string());
+        itemizer.remove(&"d".to_string());
+
+        let remap = itemizer.compact();
+        assert_eq!(
+            remap,
+            vec![
+                Some(Item::with_id(0)),
+                None,
+                Some(Item::with_id(1)),
+                None,
+                Some(Item::with_id(2))
+            ]
+        );
+        assert_eq!(itemizer.len(), 3);
+        for (old, value) in items.iter().zip(["a", "b", "c", "d", "e"]) {
+            assert_eq!(itemizer.id_of_opt(value), remap[old.as_index()]);
+        }
+        assert_eq!(
+            itemizer.values(),
+            &["a", "c", "e"].map(|value| Some(value.to_string()))
+        );
+        assert_eq!(itemizer.id_of(&"f".to_string()), Item::with_id(3));
+
+        assert_eq!(
+            itemizer.compact(),
+            (0..4).map(|id| Some(Item::with_id(id))).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_ids_of_owned() {
+        let mut itemizer = Itemizer::new();
+        let milk = itemizer.id_of(&"milk".to_string());
+
+        let items = itemizer.ids_of_owned(vec![
+            "bread".to_string(),
+            "milk".to_string(),
+            "eggs".to_string(),
+            "bread".to_string(),
+        ]);
+
+        assert_eq!(
+            items,
+            vec![Item::with_id(1), milk, Item::with_id(2), Item::with_id(1)]
+        );
+        assert_eq!(itemizer.value_of(&items[2]), "eggs");
+        assert_eq!(itemizer.len(), 3);
+    }
+
+    #[test]
+    fn test_from_sorted_values() {
+        let itemizer1 = Itemizer::from_sorted_values(vec!["milk", "bread", "eggs", "bread"]);
+        let itemizer2 = Itemizer::from_sorted_values(vec!["eggs", "milk", "bread"]);
+
+        assert_eq!(itemizer1, itemizer2);
+        assert_eq!(itemizer1.id_of_opt(&"bread"), Some(Item::with_id(0)));
+        assert_eq!(itemizer1.id_of_opt(&"eggs"), Some(Item::with_id(1)));
+        assert_eq!(itemizer1.id_of_opt(&"milk"), Some(Item::with_id(2)));
+        assert_eq!(itemizer1.len(), 3);
+    }
+
+    #[test]
+    fn test_distinct_ids_of() {
+        let mut itemizer = Itemizer::new();
+        let eggs = itemizer.id_of(&"eggs");
+
+        let items = itemizer.distinct_ids_of(&["milk", "eggs", "milk", "bread", "eggs"]);
+
+        assert_eq!(items, vec![Item::with_id(1), eggs, Item::with_id(2)]);
+        assert_eq!(itemizer.len(), 3);
+        assert_eq!(itemizer.distinct_ids_of(&[]), vec![]);
+    }
+
+    #[test]
+    fn test_item_conversions() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+
+        assert_eq!(usize::from(item2), 1);
+        assert_eq!(u32::from(item2), 1);
+        assert_eq!(Item::try_from(0).unwrap(), item1);
+        assert_eq!(itemizer[Item::try_from(1).unwrap()], "item2");
+
+        let too_large = u32::MAX as usize + 1;
+        assert!(matches!(
+            Item::<u32>::try_from(too_large),
+            Err(ItemizerError::IdOutOfRange { index }) if index == too_large
+        ));
+    }
+
+    #[test]
+    fn test_rollback() {
+        let mut itemizer = Itemizer::new();
+        let item1 = itemizer.id_of(&"item1".to_string());
+        let item2 = itemizer.id_of(&"item2".to_string());
+        itemizer.id_of(&"item3".to_string());
+        itemizer.remove(&"item2".to_string());
+
+        let checkpoint = itemizer.checkpoint();
+        assert_eq!(itemizer.id_of(&"item4".to_string()), item2);
+        let item5 = itemizer.id_of(&"item5".to_string());
+        itemizer.id_of(&"item6".to_string());
+        itemizer.remove(&"item1".to_string());
+        itemizer.rollback(checkpoint);
+
+        assert_eq!(itemizer.len(), 3);
+        assert!(!itemizer.contains("item1"));
+        assert!(!itemizer.contains("item4"));
+        assert!(!itemizer.contains("item5"));
+        assert_eq!(itemizer.id_of_opt("item3"), Some(Item::with_id(2)));
+        assert_eq!(itemizer.value_of_opt(&item2), None);
+
+        let mut reused = vec![
+            itemizer.id_of(&"item7".to_string()),
+            itemizer.id_of(&"item8".to_string()),
+        ];
+        reused.sort();
+        assert_eq!(reused, vec![item1, item2]);
+        assert_eq!(itemizer.id_of(&"item9".to_string()), item5);
+    }
+
+    #[test]
+    fn test_try_id_of_bounded() {
+        let mut itemizer = Itemizer::with_max_items(2);
+        let item1 = itemizer.try_id_of_bounded(&"item1".to_string()).unwrap();
+        let item2 = itemizer.try_id_of_bounded(&"item2".to_string()).unwrap();
+
+        assert!(matches!(
+            itemizer.try_id_of_bounded(&"item3".to_string()),
+            Err(ItemizerError::CapacityExceeded)
+        ));
+        assert_eq!(
+            itemizer.try_id_of_bounded(&"item1".to_string()).unwrap(),
+            item1
+        );
+        assert_eq!(
+            itemizer.try_id_of_bounded(&"item2".to_string()).unwrap(),
+            item2
+        );
         assert_eq!(itemizer.len(), 2);
+
+        itemizer.remove(&"item1".to_string());
+        assert_eq!(
+            itemizer.try_id_of_bounded(&"item3".to_string()).unwrap(),
+            item1
+        );
+    }
+
+    #[test]
+    fn test_common_items() {
+        let mut itemizer: Itemizer<String> = ["milk", "bread", "eggs", "butter"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+        let other: Itemizer<String> = ["butter", "jam", "milk", "eggs"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+        itemizer.remove(&"eggs".to_string());
+
+        assert_eq!(
+            itemizer.common_items(&other),
+            vec![&"milk".to_string(), &"butter".to_string()]
+        );
+        assert_eq!(other.common_items(&itemizer).len(), 2);
+        assert!(itemizer.common_items(&Itemizer::new()).is_empty());
+    }
+
+    #[test]
+    fn test_new_items_vs() {
+        let baseline: Itemizer<String> = ["milk", "bread", "eggs"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+        let mut batch: Itemizer<String> = ["eggs", "jam", "milk", "butter", "tea"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+        batch.remove(&"tea".to_string());
+
+        assert_eq!(
+            batch.new_items_vs(&baseline),
+            vec![&"jam".to_string(), &"butter".to_string()]
+        );
+        assert_eq!(baseline.new_items_vs(&batch), vec![&"bread".to_string()]);
+        assert!(baseline.new_items_vs(&baseline).is_empty());
+    }
+
+    #[test]
+    fn test_estimated_heap_bytes() {
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        assert_eq!(itemizer.estimated_heap_bytes(), 0);
+        assert_eq!(itemizer.estimated_heap_bytes_deep(), 0);
+
         itemizer.id_of(&"item1".to_string());
+        let shallow = itemizer.estimated_heap_bytes();
+        let deep = itemizer.estimated_heap_bytes_deep();
+        assert!(shallow > 0);
+        assert!(deep >= shallow + 10);
+
+        for i in 0..100 {
+            itemizer.id_of(&format!("item{}", i));
+        }
+        assert!(itemizer.estimated_heap_bytes() > shallow);
+        assert!(itemizer.estimated_heap_bytes_deep() > deep);
+    }
+
+    #[test]
+    fn test_parts_round_trip() {
+        let mut itemizer: Itemizer<String> = ["milk", "bread", "eggs"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+        let expected = itemizer.clone();
+
+        let (map, values, next) = itemizer.clone().into_parts().unwrap();
+        assert_eq!(map.len(), 3);
+        assert_eq!(values, ["milk", "bread", "eggs"].map(String::from));
+        assert_eq!(next, 3);
+
+        let mut restored = Itemizer::from_parts(map, values, next).unwrap();
+        assert_eq!(restored, expected);
+        assert_eq!(restored.id_of(&"jam".to_string()), Item::with_id(3));
+
+        itemizer.remove(&"bread".to_string());
+        itemizer.compact();
+        let (map, values, next) = itemizer.clone().into_parts().unwrap();
+        assert_eq!(Itemizer::from_parts(map, values, next).unwrap(), itemizer);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_from_parts_fnv_hash_map() {
+        let map: fnv::FnvHashMap<&str, Item> =
+            [("milk", Item::with_id(0)), ("jam", Item::with_id(1))]
+                .into_iter()
+                .collect();
+
+        let mut itemizer = Itemizer::from_parts(map, vec!["milk", "jam"], 2).unwrap();
+        assert_eq!(itemizer.id_of(&"jam"), Item::with_id(1));
+        assert_eq!(itemizer.id_of(&"tea"), Item::with_id(2));
+
+        let (map, _, _): (fnv::FnvHashMap<&str, Item>, _, _) = itemizer.into_parts().unwrap();
+        assert_eq!(map[&"tea"], Item::with_id(2));
+    }
+
+    #[test]
+    fn test_from_parts_invalid() {
+        let (map, values, _) = Itemizer::<&str>::from_iter(["a", "b"])
+            .into_parts()
+            .unwrap();
+
+        assert!(matches!(
+            Itemizer::from_parts(map.clone(), values.clone(), 1),
+            Err(ItemizerError::UnexpectedId {
+                expected: 2,
+                found: 1
+            })
+        ));
+        assert!(matches!(
+            Itemizer::from_parts(map, vec!["b", "a"], 2),
+            Err(ItemizerError::DuplicateValue { id: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_into_parts_not_dense() {
+        let mut itemizer: Itemizer<&str> = vec!["a", "b"].into_iter().collect();
+        itemizer.remove(&"a");
+        assert!(matches!(
+            itemizer.into_parts(),
+            Err(ItemizerError::NotDense { id: 0 })
+        ));
+    }
+
+    #[test]
+    fn test_into_parts_normalized() {
+        let mut itemizer = Itemizer::new_case_insensitive();
+        itemizer.id_of(&"Milk".to_string());
+        assert!(matches!(
+            itemizer.into_parts(),
+            Err(ItemizerError::Normalized)
+        ));
+    }
+
+    #[test]
+    fn test_map_values_collision() {
+        let itemizer: Itemizer<String> = ["milk", "jam", "tea"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+
+        assert!(matches!(
+            itemizer.map_values(|value| value.len()),
+            Err(ItemizerError::DuplicateValue { id: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_map_values() {
+        let mut itemizer: Itemizer<String> = ["milk", "bread", "eggs", "jam"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+        itemizer.remove(&"bread".to_string());
+
+        let mut mapped = itemizer.map_values(|value| value.to_uppercase()).unwrap();
+        assert_eq!(mapped.len(), itemizer.len());
+        for (index, value) in itemizer.slots().enumerate() {
+            let mapped_value = mapped.slots().nth(index).unwrap();
+            assert_eq!(
+                mapped_value,
+                value.map(|value| value.to_uppercase()).as_ref()
+            );
+        }
+        assert!(mapped.validate().is_ok());
+        assert_eq!(mapped.id_of(&"TEA".to_string()).as_index(), 1);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut itemizer: Itemizer<String> = ["a", "b", "c", "d", "e"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+        itemizer.remove(&"b".to_string());
+        itemizer.remove(&"d".to_string());
+
+        itemizer.truncate(3);
+        assert_eq!(itemizer.len(), 3);
+        assert_eq!(itemizer.id_of_opt("a"), Some(Item::with_id(0)));
+        assert_eq!(itemizer.id_of_opt("c"), Some(Item::with_id(2)));
+        assert!(!itemizer.contains("d"));
+        assert!(!itemizer.contains("e"));
+
+        assert_eq!(itemizer.id_of(&"f".to_string()), Item::with_id(1));
+        assert_eq!(itemizer.id_of(&"g".to_string()), Item::with_id(3));
+
+        itemizer.truncate(10);
+        assert_eq!(itemizer.len(), 4);
+    }
+
+    #[test]
+    fn test_split_at() {
+        let itemizer: Itemizer<String> = ["a", "b", "c", "d", "e"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+
+        let (mut first, mut second) = itemizer.split_at(2);
+        assert_eq!(first.len(), 2);
+        assert_eq!(second.len(), 3);
+        assert!(first.validate().is_ok());
+        assert!(second.validate().is_ok());
+
+        assert_eq!(first.id_of_opt("a"), Some(Item::with_id(0)));
+        assert_eq!(first.id_of_opt("b"), Some(Item::with_id(1)));
+        assert!(!first.contains("c"));
+        for (index, value) in ["c", "d", "e"].iter().enumerate() {
+            assert_eq!(second.id_of_opt(*value), Some(Item::with_id(index as u32)));
+            assert_eq!(second.value_of(&Item::with_id(index as u32)), value);
+        }
+        assert!(!second.contains("a"));
+
+        assert_eq!(first.id_of(&"f".to_string()), Item::with_id(2));
+        assert_eq!(second.id_of(&"f".to_string()), Item::with_id(3));
+    }
+
+    #[test]
+    fn test_split_at_with_free_ids() {
+        let mut itemizer: Itemizer<String> = ["a", "b", "c", "d", "e"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+        itemizer.remove(&"a".to_string());
+        itemizer.remove(&"d".to_string());
+
+        let (mut first, mut second) = itemizer.split_at(2);
+        assert!(first.validate().is_ok());
+        assert!(second.validate().is_ok());
+        assert_eq!(second.id_of_opt("e"), Some(Item::with_id(2)));
+        assert_eq!(first.id_of(&"x".to_string()), Item::with_id(0));
+        assert_eq!(second.id_of(&"x".to_string()), Item::with_id(1));
+    }
+
+    #[test]
+    fn test_split_at_case_insensitive() {
+        let mut itemizer = Itemizer::new_case_insensitive();
+        itemizer.ids_of(&["Milk", "Bread", "Eggs", "Jam"].map(String::from));
+        itemizer.remove(&"bread".to_string());
+
+        let (first, mut second) = itemizer.split_at(2);
+        assert!(first.validate().is_ok());
+        assert!(second.validate().is_ok());
+        assert_eq!(first.id_of_opt("MILK"), Some(Item::with_id(0)));
+        assert_eq!(second.id_of_opt("eggs"), Some(Item::with_id(0)));
+        assert_eq!(second.id_of_opt("jam"), Some(Item::with_id(1)));
+        assert_eq!(second.value_of(&Item::with_id(0)), "Eggs");
+        assert!(!first.contains("eggs"));
+        assert_eq!(second.id_of(&"JAM".to_string()), Item::with_id(1));
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut itemizer = Itemizer::new();
+        let items = itemizer.ids_of(&["milk", "bread", "milk", "eggs", "milk", "bread"]);
+        itemizer.id_of_owned("eggs");
+        itemizer.id_of_opt(&"milk");
+
+        assert_eq!(
+            itemizer.stats(),
+            ItemizerStats {
+                lookups: 7,
+                hits: 4,
+                inserts: 3
+            }
+        );
+        assert_eq!(
+            items,
+            itemizer
+                .ids_of_opt(&["milk", "bread", "milk", "eggs", "milk", "bread"])
+                .into_iter()
+                .flatten()
+                .collect::<Vec<_>>()
+        );
+
+        itemizer.remove(&"milk");
+        itemizer.id_of(&"jam");
+        assert_eq!(itemizer.stats().inserts, 4);
+    }
+
+    #[test]
+    fn test_apply_remap() {
+        let mut itemizer: Itemizer<&str> =
+            vec!["milk", "bread", "eggs", "jam"].into_iter().collect();
+        let transaction = itemizer.ids_of(&["jam", "bread", "milk", "eggs"]);
+
+        let remap = itemizer.retain(|value| *value != "bread");
+        let transaction = crate::apply_remap(&transaction, &remap);
+
+        assert_eq!(
+            itemizer.decode(&transaction),
+            vec![&"jam", &"milk", &"eggs"]
+        );
+        assert_eq!(crate::apply_remap(&[Item::with_id(9)], &remap), vec![]);
+    }
+
+    #[test]
+    fn test_value_of_unchecked() {
+        let mut itemizer = Itemizer::new();
+        let items = itemizer.ids_of(&["milk", "bread", "eggs"]);
+
+        for item in &items {
+            // SAFETY: all items were returned by `itemizer`.
+            let value = unsafe { itemizer.value_of_unchecked(item) };
+            assert_eq!(value, itemizer.value_of(item));
+        }
+    }
+
+    #[test]
+    fn test_itemize_weighted() {
+        let mut itemizer = Itemizer::new();
+        let bread = itemizer.id_of(&"bread".to_string());
+
+        let items = itemizer.itemize_weighted(&[
+            ("milk".to_string(), 1_000_000),
+            ("bread".to_string(), 2),
+            ("milk".to_string(), 0),
+        ]);
+
+        assert_eq!(
+            items,
+            vec![
+                (Item::with_id(1), 1_000_000),
+                (bread, 2),
+                (Item::with_id(1), 0)
+            ]
+        );
         assert_eq!(itemizer.len(), 2);
     }
 
+    #[cfg(feature = "std")]
     #[test]
-    fn test_iter() {
+    fn test_iter_unordered() {
+        use std::collections::HashMap;
+
+        let mut itemizer: Itemizer<&str> = vec!["milk", "bread", "eggs"].into_iter().collect();
+        itemizer.remove(&"bread");
+
+        let pairs: HashMap<&str, Item> = itemizer
+            .iter_unordered()
+            .map(|(value, item)| (*value, item))
+            .collect();
+        let expected: HashMap<&str, Item> =
+            [("milk", Item::with_id(0)), ("eggs", Item::with_id(2))].into();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn test_intern_new_block() {
         let mut itemizer = Itemizer::new();
-        itemizer.id_of(&"item1".to_string());
-        itemizer.id_of(&"item2".to_string());
+        itemizer.ids_of(&["milk", "bread"]);
+        itemizer.remove(&"milk");
 
-        let mut iter = itemizer.iter();
-        assert_eq!(iter.next(), Some(&"item1".to_string()));
-        assert_eq!(iter.next(), Some(&"item2".to_string()));
-        assert_eq!(iter.next(), None);
+        let range = itemizer.intern_new_block(&["eggs", "jam", "tea"]);
+        assert_eq!(range.start(), Item::with_id(2));
+        assert_eq!(range.len(), 3);
+        assert_eq!(
+            range
+                .into_iter()
+                .map(|item| *itemizer.value_of(&item))
+                .collect::<Vec<_>>(),
+            vec!["eggs", "jam", "tea"]
+        );
+        assert_eq!(itemizer.id_of_opt(&"tea"), Some(Item::with_id(4)));
+        assert_eq!(itemizer.id_of(&"butter"), Item::with_id(0));
+        assert_eq!(itemizer.id_of(&"cheese"), Item::with_id(5));
+    }
+
+    #[test]
+    #[should_panic(expected = "value at index 1 of the block is not new")]
+    fn test_intern_new_block_existing() {
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&"milk");
+        itemizer.intern_new_block(&["eggs", "milk"]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_intern_new_block_duplicate_leaves_itemizer_unchanged() {
+        let mut itemizer = Itemizer::new();
+        itemizer.id_of(&"milk");
+        let before = itemizer.clone();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            itemizer.intern_new_block(&["eggs", "jam", "eggs"])
+        }));
+        assert!(result.is_err());
+        assert_eq!(itemizer, before);
+    }
+
+    #[test]
+    fn test_drain_filter() {
+        let mut itemizer: Itemizer<String> = ["apple", "bread", "avocado", "milk", "apricot"]
+            .map(String::from)
+            .into_iter()
+            .collect();
+        itemizer.remove(&"milk".to_string());
+
+        let (drained, remap) = itemizer.drain_filter(|value| value.starts_with('a'));
+        assert_eq!(
+            drained,
+            vec![
+                (Item::with_id(0), "apple".to_string()),
+                (Item::with_id(2), "avocado".to_string()),
+                (Item::with_id(4), "apricot".to_string()),
+            ]
+        );
+        assert_eq!(remap, vec![None, Some(Item::with_id(0)), None, None, None]);
+
+        assert_eq!(itemizer.len(), 1);
+        assert_eq!(itemizer.values(), &[Some("bread".to_string())]);
+        assert!(!itemizer.contains("apple"));
+        assert!(itemizer.validate().is_ok());
+        assert_eq!(itemizer.id_of(&"jam".to_string()), Item::with_id(1));
+    }
+
+    #[test]
+    fn test_drain_filter_moves_values() {
+        let mut itemizer = Itemizer::new_case_insensitive();
+        let milk = itemizer.id_of(&"Milk".to_string());
+        itemizer.id_of(&"Bread".to_string());
+        let buffer = itemizer.value_of(&milk).as_ptr();
+
+        let (drained, _) = itemizer.drain_filter(|value| value == "Milk");
+        assert_eq!(drained[0].1.as_ptr(), buffer);
+        assert!(!itemizer.contains(&"MILK".to_string()));
+        assert!(itemizer.validate().is_ok());
+    }
+
+    #[test]
+    fn test_assert_dense() {
+        let mut itemizer: Itemizer<&str> = vec!["a", "b", "c", "d"].into_iter().collect();
+        assert!(itemizer.assert_dense().is_ok());
+
+        itemizer.remove(&"c");
+        itemizer.remove(&"b");
+        assert!(matches!(
+            itemizer.assert_dense(),
+            Err(ItemizerError::NotDense { id: 1 })
+        ));
+
+        itemizer.id_of(&"e");
+        assert!(matches!(
+            itemizer.assert_dense(),
+            Err(ItemizerError::NotDense { id: 2 })
+        ));
+
+        itemizer.compact();
+        assert!(itemizer.assert_dense().is_ok());
+        assert_eq!(itemizer.len(), 3);
+
+        itemizer.next_item_id = 4;
+        assert!(matches!(
+            itemizer.assert_dense(),
+            Err(ItemizerError::NotDense { id: 3 })
+        ));
+        itemizer.next_item_id = 2;
+        assert!(matches!(
+            itemizer.assert_dense(),
+            Err(ItemizerError::NotDense { id: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_validate() {
+        let valid: Itemizer<&str> = vec!["a", "b", "c"].into_iter().collect();
+        assert!(valid.validate().is_ok());
+
+        let mut itemizer = valid.clone();
+        itemizer.next_item_id = 5;
+        assert!(matches!(
+            itemizer.validate(),
+            Err(ItemizerError::UnexpectedId {
+                expected: 3,
+                found: 5
+            })
+        ));
+
+        let mut itemizer = valid.clone();
+        itemizer.item_str_to_id.insert("b", Item::with_id(0));
+        assert!(matches!(
+            itemizer.validate(),
+            Err(ItemizerError::DuplicateValue { id: 1 })
+        ));
+
+        let mut itemizer = valid.clone();
+        itemizer.item_str_to_id.remove("c");
+        assert!(matches!(
+            itemizer.validate(),
+            Err(ItemizerError::UnmappedValue { id: 2 })
+        ));
+
+        let mut itemizer = valid.clone();
+        itemizer.item_str_to_id.insert("d", Item::with_id(1));
+        assert!(matches!(
+            itemizer.validate(),
+            Err(ItemizerError::LengthMismatch { values: 3, ids: 4 })
+        ));
+    }
+
+    #[test]
+    fn test_validate_case_insensitive() {
+        let mut itemizer = Itemizer::new_case_insensitive();
+        itemizer.ids_of(&["Milk".to_string(), "EGGS".to_string(), "milk".to_string()]);
+        itemizer.remove(&"eggs".to_string());
+        itemizer.id_of(&"Jam".to_string());
+        assert!(itemizer.validate().is_ok());
+
+        itemizer.item_str_to_id.remove("jam");
+        assert!(matches!(
+            itemizer.validate(),
+            Err(ItemizerError::UnmappedValue { id: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_one_hot() {
+        let mut itemizer = Itemizer::new();
+        let transactions = vec![
+            itemizer.ids_of(&["milk", "eggs", "milk"]),
+            itemizer.ids_of(&["bread", "eggs"]),
+            vec![],
+        ];
+
+        assert_eq!(
+            itemizer.one_hot(&transactions),
+            vec![vec![1, 1, 0], vec![0, 1, 1], vec![0, 0, 0]]
+        );
+    }
+
+    #[test]
+    fn test_support_counts() {
+        let mut itemizer = Itemizer::new();
+        let transactions = vec![
+            itemizer.ids_of(&["milk", "bread", "milk"]),
+            itemizer.ids_of(&["bread", "eggs"]),
+            itemizer.ids_of(&["milk", "bread", "eggs", "bread"]),
+            vec![],
+        ];
+        itemizer.id_of(&"jam");
+
+        assert_eq!(itemizer.support_counts(&transactions), vec![2, 3, 2, 0]);
+        assert_eq!(itemizer.support_counts(&[]), vec![0; 4]);
+    }
+
+    #[test]
+    fn test_from_id_map() {
+        let map: FnvHashMap<String, u32> = [("milk", 2), ("bread", 0), ("eggs", 1)]
+            .into_iter()
+            .map(|(value, id)| (value.to_string(), id))
+            .collect();
+        let mut itemizer = Itemizer::from_id_map(map).unwrap();
+
+        assert_eq!(itemizer.len(), 3);
+        assert_eq!(itemizer.id_of_opt("milk"), Some(Item::with_id(2)));
+        assert_eq!(itemizer.value_of(&Item::with_id(0)), "bread");
+        assert_eq!(itemizer.id_of(&"jam".to_string()), Item::with_id(3));
+    }
+
+    #[test]
+    fn test_from_id_map_not_dense() {
+        let gapped: FnvHashMap<&str, u32> = [("milk", 0), ("bread", 2)].into_iter().collect();
+        assert!(matches!(
+            Itemizer::from_id_map(gapped),
+            Err(ItemizerError::NotDense { id: 1 })
+        ));
+
+        let duplicate: FnvHashMap<&str, u32> = [("milk", 0), ("bread", 0)].into_iter().collect();
+        assert!(matches!(
+            Itemizer::from_id_map(duplicate),
+            Err(ItemizerError::IdOccupied { id: 0 } | ItemizerError::NotDense { id: 1 })
+        ));
+
+        assert!(matches!(
+            Itemizer::from_id_map(vec![("milk", 0), ("milk", 1)]),
+            Err(ItemizerError::DuplicateValue { id: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_reverse_iteration() {
+        let mut itemizer = Itemizer::new();
+        itemizer.ids_of(&["milk", "bread", "eggs"]);
+
+        let reversed: Vec<Item> = itemizer.iter_items().rev().map(|(item, _)| item).collect();
+        assert_eq!(
+            reversed,
+            vec![Item::with_id(2), Item::with_id(1), Item::with_id(0)]
+        );
+        assert_eq!(itemizer.iter_items().len(), 3);
+        assert_eq!(itemizer.enumerate().next_back(), Some((2, &"eggs")));
+        assert_eq!(itemizer.enumerate().len(), 3);
+        assert_eq!(itemizer.iter_ids().next_back(), Some(Item::with_id(2)));
+
+        let mut iter = itemizer.into_iter();
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.next_back(), Some((Item::with_id(2), "eggs")));
+        assert_eq!(iter.next(), Some((Item::with_id(0), "milk")));
+        assert_eq!(iter.len(), 1);
+    }
+
+    #[test]
+    fn test_with_prefix() {
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        let items =
+            itemizer.ids_of(&["bread", "butter", "milk", "buttermilk", "bun"].map(String::from));
+        itemizer.remove(&"bun".to_string());
+
+        let matches: Vec<(Item, &String)> = itemizer.with_prefix("bu").collect();
+        assert_eq!(
+            matches,
+            vec![
+                (items[1], &"butter".to_string()),
+                (items[3], &"buttermilk".to_string())
+            ]
+        );
+        assert_eq!(itemizer.with_prefix("").count(), 4);
+        assert_eq!(itemizer.with_prefix("x").count(), 0);
+
+        let matches = itemizer.with_prefix(&String::from("bu"));
+        assert_eq!(matches.count(), 2);
+    }
+
+    #[test]
+    fn test_from_values_by_key() {
+        let values = ["milk", "jam", "bread", "tea", "eggs", "jam"]
+            .map(String::from)
+            .to_vec();
+        let itemizer = Itemizer::from_values_by_key(values, |value| (value.len(), value.clone()));
+
+        assert_eq!(
+            itemizer.values(),
+            &["jam", "tea", "eggs", "milk", "bread"].map(|value| Some(value.to_string()))
+        );
+        assert_eq!(itemizer.id_of_opt("eggs"), Some(Item::with_id(2)));
+    }
+
+    #[test]
+    fn test_extend_counting() {
+        let mut itemizer = Itemizer::new();
+        assert_eq!(itemizer.extend_counting(&["milk", "bread", "milk"]), 2);
+        assert_eq!(
+            itemizer.extend_counting(&["bread", "eggs", "jam", "eggs", "milk"]),
+            2
+        );
+        assert_eq!(itemizer.extend_counting(&["jam"]), 0);
+        assert_eq!(itemizer.extend_counting(&[]), 0);
+        assert_eq!(itemizer.len(), 4);
+
+        itemizer.remove(&"milk");
+        assert_eq!(itemizer.extend_counting(&["milk"]), 1);
+        assert_eq!(itemizer.len(), 4);
+    }
+
+    #[test]
+    fn test_into_values() {
+        let mut itemizer = Itemizer::new();
+        let items = itemizer.ids_of(&["milk".to_string(), "bread".to_string(), "eggs".to_string()]);
+
+        let values = itemizer.into_values();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[items[0].as_index()].as_deref(), Some("milk"));
+        assert_eq!(values[items[2].as_index()].as_deref(), Some("eggs"));
     }
 
     #[test]
@@ -0,0 +1,111 @@
+//!
+//! Iterators over the contents of an `Itemizer`.
+//!
+
+use crate::item::{Item, ItemId};
+use alloc::vec::{self, Vec};
+use core::iter::Enumerate;
+use core::marker::PhantomData;
+use core::slice;
+
+/// An owning iterator over the `(Item, T)` pairs of an `Itemizer`, in id
+/// order. Ids freed by `remove` are skipped.
+///
+/// This is created by the `into_iter` method on `Itemizer`.
+pub struct IntoIter<T, I = u32> {
+    inner: Enumerate<vec::IntoIter<Option<T>>>,
+    remaining: usize,
+    id_type: PhantomData<I>,
+}
+
+impl<T, I> IntoIter<T, I> {
+    pub(crate) fn new(slots: Vec<Option<T>>, live: usize) -> IntoIter<T, I> {
+        IntoIter {
+            inner: slots.into_iter().enumerate(),
+            remaining: live,
+            id_type: PhantomData,
+        }
+    }
+}
+
+impl<T, I> Iterator for IntoIter<T, I>
+where
+    I: ItemId,
+{
+    type Item = (Item<I>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (index, value) = self
+            .inner
+            .find_map(|(index, value)| Some((index, value?)))?;
+        self.remaining -= 1;
+        Some((Item::at_index(index), value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T, I> DoubleEndedIterator for IntoIter<T, I>
+where
+    I: ItemId,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (index, value) = self
+            .inner
+            .by_ref()
+            .rev()
+            .find_map(|(index, value)| Some((index, value?)))?;
+        self.remaining -= 1;
+        Some((Item::at_index(index), value))
+    }
+}
+
+impl<T, I> ExactSizeIterator for IntoIter<T, I> where I: ItemId {}
+
+/// A borrowing iterator over the index and value of the live slots of an
+/// `Itemizer`, in id order. Slots of ids freed by `remove` are skipped.
+pub(crate) struct Slots<'a, T> {
+    inner: Enumerate<slice::Iter<'a, Option<T>>>,
+    remaining: usize,
+}
+
+impl<'a, T> Slots<'a, T> {
+    pub(crate) fn new(slots: &'a [Option<T>], live: usize) -> Slots<'a, T> {
+        Slots {
+            inner: slots.iter().enumerate(),
+            remaining: live,
+        }
+    }
+}
+
+impl<'a, T> Iterator for Slots<'a, T> {
+    type Item = (usize, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self
+            .inner
+            .find_map(|(index, value)| Some((index, value.as_ref()?)))?;
+        self.remaining -= 1;
+        Some(next)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<T> DoubleEndedIterator for Slots<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let next = self
+            .inner
+            .by_ref()
+            .rev()
+            .find_map(|(index, value)| Some((index, value.as_ref()?)))?;
+        self.remaining -= 1;
+        Some(next)
+    }
+}
+
+impl<T> ExactSizeIterator for Slots<'_, T> {}
@@ -17,8 +17,51 @@
 //! ```
 //!
 
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "bincode")]
+mod binary;
+#[cfg(feature = "csv")]
+mod column;
+#[cfg(feature = "std")]
+mod concurrent;
+mod error;
+mod frozen;
+mod heap;
 mod item;
 mod itemizer;
+mod iter;
+#[cfg(feature = "std")]
+mod log;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod set;
+mod sorted;
+mod string;
+#[cfg(feature = "std")]
+mod table;
+#[cfg(feature = "std")]
+mod transaction;
+
+#[cfg(not(feature = "std"))]
+use hashbrown::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::collections::{HashMap, HashSet};
 
-pub use item::Item;
-pub use itemizer::Itemizer;
+#[cfg(feature = "std")]
+pub use concurrent::ConcurrentItemizer;
+pub use error::ItemizerError;
+pub use frozen::FrozenItemizer;
+pub use heap::HeapSize;
+pub use item::{apply_remap, items_as_u32, rebase, Item, ItemId, ItemRange};
+pub use itemizer::{Checkpoint, Itemizer, ItemizerStats};
+pub use iter::IntoIter;
+#[cfg(feature = "std")]
+pub use log::LoggedItemizer;
+pub use set::ItemSet;
+pub use sorted::SortedItemizer;
+pub use string::StrItemizer;
+#[cfg(feature = "std")]
+pub use transaction::TransactionEncoder;
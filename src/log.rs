@@ -0,0 +1,388 @@
+//!
+//! An itemizer that persists every new item to an append-only log.
+//!
+//! The log holds one value per line in id order. Opening it replays the log,
+//! so every value gets back the id it had before, and each value interned
+//! afterwards is appended and synced to disk before its `Item` is returned.
+//! A line cut short by a crash is dropped when the log is opened again.
+//!
+//! # Examples
+//!
+//! ```
+//! use itemizer::LoggedItemizer;
+//!
+//! let path = std::env::temp_dir().join("itemizer-log-example.log");
+//! # let _ = std::fs::remove_file(&path);
+//!
+//! let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+//! let milk = itemizer.id_of("milk").unwrap();
+//! drop(itemizer);
+//!
+//! let itemizer = LoggedItemizer::open_log(&path).unwrap();
+//! assert_eq!(itemizer.id_of_opt("milk"), Some(milk));
+//! # std::fs::remove_file(&path).unwrap();
+//! ```
+//!
+
+use crate::error::ItemizerError;
+use crate::item::Item;
+use crate::itemizer::Itemizer;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// An `Itemizer` that persists every new item to an append-only log, created
+/// by `open_log`.
+pub struct LoggedItemizer<T> {
+    itemizer: Itemizer<T>,
+    log: File,
+    // The length of the complete lines of the log.
+    end: u64,
+    // Whether a failed append may have left a partial line after `end`.
+    torn: bool,
+}
+
+impl LoggedItemizer<String> {
+    /// Opens the log at `path`, creating it if it does not exist, and
+    /// replays it into a new `LoggedItemizer`.
+    ///
+    /// Fails with `ItemizerError::DuplicateValue` if a value appears twice
+    /// in the log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::LoggedItemizer;
+    ///
+    /// let path = std::env::temp_dir().join("itemizer-open-log-example.log");
+    /// # let _ = std::fs::remove_file(&path);
+    /// std::fs::write(&path, "milk\nbread\n").unwrap();
+    ///
+    /// let itemizer = LoggedItemizer::open_log(&path).unwrap();
+    /// assert_eq!(itemizer.id_of_opt("bread").unwrap().as_index(), 1);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    pub fn open_log<P: AsRef<Path>>(path: P) -> Result<LoggedItemizer<String>, ItemizerError> {
+        let mut log = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .create(true)
+            .open(path)?;
+
+        let mut contents = String::new();
+        log.read_to_string(&mut contents)?;
+        // Everything after the last line break is a write cut short by a
+        // crash, the value it belongs to was never handed out.
+        let complete = contents.rfind('\n').map_or(0, |end| end + 1);
+        if complete < contents.len() {
+            log.set_len(complete as u64)?;
+        }
+
+        let mut itemizer = Itemizer::new();
+        for (id, value) in contents[..complete].lines().enumerate() {
+            let value = value.to_string();
+            if itemizer.contains(&value) {
+                return Err(ItemizerError::DuplicateValue { id });
+            }
+            itemizer.try_id_of(&value)?;
+        }
+
+        Ok(LoggedItemizer {
+            itemizer,
+            log,
+            end: complete as u64,
+            torn: false,
+        })
+    }
+
+    /// Returns the `Item` for the given value. If the value is new, it is
+    /// assigned an id and appended to the log before its `Item` is returned.
+    /// If the log cannot be written, the assignment is undone and the log is
+    /// cut back to its last complete line.
+    ///
+    /// Values containing line breaks cannot be logged and are rejected with
+    /// an `ItemizerError::Io` of kind `InvalidInput`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::LoggedItemizer;
+    ///
+    /// let path = std::env::temp_dir().join("itemizer-logged-id-of-example.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+    /// let milk = itemizer.id_of("milk").unwrap();
+    ///
+    /// assert_eq!(itemizer.id_of("milk").unwrap(), milk);
+    /// assert_eq!(std::fs::read_to_string(&path).unwrap(), "milk\n");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    pub fn id_of(&mut self, item: &str) -> Result<Item, ItemizerError> {
+        if let Some(id) = self.itemizer.id_of_opt(item) {
+            return Ok(id);
+        }
+        if item.contains(['\n', '\r']) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "logged values cannot contain line breaks",
+            )
+            .into());
+        }
+
+        if self.torn {
+            self.log.set_len(self.end)?;
+            self.torn = false;
+        }
+
+        let checkpoint = self.itemizer.checkpoint();
+        let id = self.itemizer.try_id_of(&item.to_string())?;
+        let line = format!("{}\n", item);
+        let written = self
+            .log
+            .write_all(line.as_bytes())
+            .and_then(|()| self.log.sync_data());
+        if let Err(err) = written {
+            self.itemizer.rollback(checkpoint);
+            // Drops what was written of the line, so the next append does
+            // not join onto it. If that fails too, it is retried then.
+            self.torn = self.log.set_len(self.end).is_err();
+            return Err(err.into());
+        }
+        self.end += line.len() as u64;
+        Ok(id)
+    }
+
+    /// Returns the `Item` for the given value if it exists in the
+    /// `LoggedItemizer`. If the value is not present, `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::LoggedItemizer;
+    ///
+    /// let path = std::env::temp_dir().join("itemizer-logged-id-of-opt-example.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+    /// let milk = itemizer.id_of("milk").unwrap();
+    ///
+    /// assert_eq!(itemizer.id_of_opt("milk"), Some(milk));
+    /// assert_eq!(itemizer.id_of_opt("bread"), None);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    pub fn id_of_opt(&self, item: &str) -> Option<Item> {
+        self.itemizer.id_of_opt(item)
+    }
+
+    /// Returns the value of the given `Item`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::LoggedItemizer;
+    ///
+    /// let path = std::env::temp_dir().join("itemizer-logged-value-of-example.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+    /// let milk = itemizer.id_of("milk").unwrap();
+    ///
+    /// assert_eq!(itemizer.value_of(&milk), "milk");
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    pub fn value_of(&self, id: &Item) -> &str {
+        self.itemizer.value_of(id)
+    }
+
+    /// Returns the number of values in the `LoggedItemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::LoggedItemizer;
+    ///
+    /// let path = std::env::temp_dir().join("itemizer-logged-len-example.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+    /// itemizer.id_of("milk").unwrap();
+    ///
+    /// assert_eq!(itemizer.len(), 1);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    pub fn len(&self) -> usize {
+        self.itemizer.len()
+    }
+
+    /// Returns `true` if the `LoggedItemizer` contains no values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::LoggedItemizer;
+    ///
+    /// let path = std::env::temp_dir().join("itemizer-logged-is-empty-example.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+    /// assert!(itemizer.is_empty());
+    ///
+    /// itemizer.id_of("milk").unwrap();
+    /// assert!(!itemizer.is_empty());
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.itemizer.is_empty()
+    }
+
+    /// Returns the underlying `Itemizer`, e.g. to encode transactions with
+    /// it without logging new values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::LoggedItemizer;
+    ///
+    /// let path = std::env::temp_dir().join("itemizer-logged-itemizer-example.log");
+    /// # let _ = std::fs::remove_file(&path);
+    ///
+    /// let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+    /// let milk = itemizer.id_of("milk").unwrap();
+    ///
+    /// assert_eq!(itemizer.itemizer().ids_of_opt(&["milk".to_string()]), vec![Some(milk)]);
+    /// # std::fs::remove_file(&path).unwrap();
+    /// ```
+    ///
+    pub fn itemizer(&self) -> &Itemizer<String> {
+        &self.itemizer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn temp_log(name: &str) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("itemizer-{}-{}.log", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn test_reopen_log() {
+        let path = temp_log("reopen");
+
+        let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+        let items: Vec<Item> = ["milk", "bread", "milk", "eggs"]
+            .iter()
+            .map(|value| itemizer.id_of(value).unwrap())
+            .collect();
+        drop(itemizer);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "milk\nbread\neggs\n");
+
+        let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+        assert_eq!(itemizer.len(), 3);
+        for (value, item) in ["milk", "bread", "milk", "eggs"].iter().zip(&items) {
+            assert_eq!(itemizer.id_of_opt(value), Some(*item));
+            assert_eq!(itemizer.value_of(item), *value);
+        }
+        assert_eq!(itemizer.id_of("jam").unwrap().as_index(), 3);
+        drop(itemizer);
+
+        let itemizer = LoggedItemizer::open_log(&path).unwrap();
+        assert_eq!(itemizer.id_of_opt("jam").unwrap().as_index(), 3);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_torn_write() {
+        let path = temp_log("torn");
+        fs::write(&path, "milk\nbread\nja").unwrap();
+
+        let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+        assert_eq!(itemizer.len(), 2);
+        assert!(itemizer.id_of_opt("ja").is_none());
+        assert_eq!(itemizer.id_of("jam").unwrap().as_index(), 2);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "milk\nbread\njam\n");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_log_errors() {
+        let path = temp_log("errors");
+        fs::write(&path, "milk\nbread\nmilk\n").unwrap();
+        assert!(matches!(
+            LoggedItemizer::open_log(&path),
+            Err(ItemizerError::DuplicateValue { id: 2 })
+        ));
+
+        fs::write(&path, "").unwrap();
+        let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+        assert!(matches!(
+            itemizer.id_of("milk\nbread"),
+            Err(ItemizerError::Io(err)) if err.kind() == io::ErrorKind::InvalidInput
+        ));
+        assert!(itemizer.is_empty());
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_failed_append_is_undone() {
+        let path = temp_log("read-only");
+        fs::write(&path, "milk\n").unwrap();
+
+        let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+        itemizer.log = File::open(&path).unwrap();
+        assert!(matches!(itemizer.id_of("bread"), Err(ItemizerError::Io(_))));
+        assert_eq!(itemizer.len(), 1);
+        assert!(itemizer.id_of_opt("bread").is_none());
+
+        itemizer.log = OpenOptions::new().append(true).open(&path).unwrap();
+        assert_eq!(itemizer.id_of("bread").unwrap().as_index(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "milk\nbread\n");
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_failed_append_drops_partial_line() {
+        let path = temp_log("partial");
+        fs::write(&path, "milk\n").unwrap();
+
+        let mut itemizer = LoggedItemizer::open_log(&path).unwrap();
+        // Stands in for the part of a line written before an append failed.
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"bre")
+            .unwrap();
+        itemizer.log = File::open(&path).unwrap();
+        assert!(matches!(itemizer.id_of("bread"), Err(ItemizerError::Io(_))));
+
+        itemizer.log = OpenOptions::new().append(true).open(&path).unwrap();
+        assert_eq!(itemizer.id_of("jam").unwrap().as_index(), 1);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "milk\njam\n");
+        drop(itemizer);
+
+        let itemizer = LoggedItemizer::open_log(&path).unwrap();
+        assert_eq!(itemizer.id_of_opt("jam").unwrap().as_index(), 1);
+        assert!(itemizer.id_of_opt("brejam").is_none());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
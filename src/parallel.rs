@@ -0,0 +1,134 @@
+//!
+//! Interning of many items at once on all cores, using rayon.
+//!
+
+use crate::item::{Item, ItemId};
+use crate::itemizer::Itemizer;
+use crate::HashMap;
+use fnv::FnvBuildHasher;
+use rayon::prelude::*;
+use std::borrow::Cow;
+use std::hash::{BuildHasher, Hash};
+
+impl<T, I, S> Itemizer<T, I, S>
+where
+    T: Eq + Hash + Clone + Send + Sync,
+    I: ItemId + Send + Sync,
+    S: BuildHasher + Sync,
+{
+    /// Returns the `Item`s for all given items in order like `ids_of`,
+    /// doing the lookups in parallel.
+    ///
+    /// The items are first looked up in parallel, which also collects the
+    /// distinct new ones. These are then added in one sequential pass in
+    /// order of their first occurrence in `items`, and the final mapping is
+    /// again done in parallel. The result and `stats` are the same as those
+    /// of `ids_of`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let items = itemizer.par_intern(&["a", "b", "a"]);
+    ///
+    /// assert_eq!(items, vec![itemizer.id_of(&"a"), itemizer.id_of(&"b"), itemizer.id_of(&"a")]);
+    /// ```
+    ///
+    pub fn par_intern(&mut self, items: &[T]) -> Vec<Item<I>> {
+        let found: Vec<Option<Item<I>>> =
+            items.par_iter().map(|item| self.id_of_opt(item)).collect();
+
+        // Keeps the first occurrence of every new key, so that the ids are
+        // assigned in the same order as by `ids_of`.
+        let mut new_items: Vec<(usize, &T)> = items
+            .par_iter()
+            .zip(&found)
+            .enumerate()
+            .filter(|(_, (_, id))| id.is_none())
+            .fold(
+                HashMap::<Cow<T>, (usize, &T), FnvBuildHasher>::default,
+                |mut first, (index, (item, _))| {
+                    first.entry(self.key(item)).or_insert((index, item));
+                    first
+                },
+            )
+            .reduce(HashMap::default, |mut first, other| {
+                for (key, (index, item)) in other {
+                    let entry = first.entry(key).or_insert((index, item));
+                    if index < entry.0 {
+                        *entry = (index, item);
+                    }
+                }
+                first
+            })
+            .into_values()
+            .collect();
+        new_items.sort_unstable_by_key(|(index, _)| *index);
+
+        for (_, item) in &new_items {
+            if let Err(err) = self.insert_new((*item).clone()) {
+                panic!("{}", err);
+            }
+        }
+        self.count_lookups(items.len(), items.len() - new_items.len());
+
+        let itemizer = &*self;
+        found
+            .into_par_iter()
+            .zip(items)
+            .map(|(id, item)| match id {
+                Some(id) => id,
+                None => itemizer
+                    .id_of_opt(item)
+                    .expect("new items are added before the final mapping"),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_par_intern() {
+        let items: Vec<String> = (0..10_000).map(|i| format!("item{}", i % 997)).collect();
+
+        let mut sequential = Itemizer::new();
+        sequential.id_of(&"item5".to_string());
+        let mut parallel = sequential.clone();
+
+        assert_eq!(parallel.par_intern(&items), sequential.ids_of(&items));
+        assert_eq!(parallel, sequential);
+    }
+
+    #[test]
+    fn test_par_intern_stats() {
+        let items: Vec<String> = (0..10_000).map(|i| format!("item{}", i % 997)).collect();
+
+        let mut sequential = Itemizer::new();
+        sequential.ids_of(&items[..100]);
+        let mut parallel = sequential.clone();
+
+        parallel.par_intern(&items);
+        sequential.ids_of(&items);
+        assert_eq!(parallel.stats(), sequential.stats());
+    }
+
+    #[test]
+    fn test_par_intern_case_insensitive() {
+        let items: Vec<String> = ["Milk", "eggs", "milk", "EGGS", "jam"]
+            .iter()
+            .map(|value| value.to_string())
+            .collect();
+
+        let mut sequential = Itemizer::new_case_insensitive();
+        let mut parallel = Itemizer::new_case_insensitive();
+
+        assert_eq!(parallel.par_intern(&items), sequential.ids_of(&items));
+        assert_eq!(parallel, sequential);
+        assert_eq!(parallel.stats(), sequential.stats());
+    }
+}
@@ -0,0 +1,301 @@
+//!
+//! A set of `Item`s stored as a bit vector.
+//!
+//! `ItemSet` keeps one bit per id, so membership tests, unions and
+//! intersections are cheap for the dense ids assigned by an `Itemizer`.
+//!
+//! # Examples
+//!
+//! ```
+//! use itemizer::{ItemSet, Itemizer};
+//!
+//! let mut itemizer = Itemizer::new();
+//! let milk = itemizer.id_of(&"milk");
+//! let bread = itemizer.id_of(&"bread");
+//!
+//! let mut basket = ItemSet::with_capacity(itemizer.len());
+//! basket.insert(milk);
+//!
+//! assert!(basket.contains(milk));
+//! assert!(!basket.contains(bread));
+//! ```
+//!
+
+use crate::item::{Item, ItemId};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A set of `Item`s with one bit per id, grown as larger ids are
+/// inserted.
+#[derive(Clone, Debug)]
+pub struct ItemSet<I = u32> {
+    words: Vec<u64>,
+    id: PhantomData<I>,
+}
+
+impl<I: ItemId> ItemSet<I> {
+    /// Creates a new, empty `ItemSet` with room for the ids `0..n`. Larger
+    /// ids can still be inserted, the set grows as needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ItemSet;
+    ///
+    /// let set: ItemSet = ItemSet::with_capacity(100);
+    /// assert!(set.is_empty());
+    /// ```
+    ///
+    pub fn with_capacity(n: usize) -> ItemSet<I> {
+        ItemSet {
+            words: vec![0; n.div_ceil(BITS)],
+            id: PhantomData,
+        }
+    }
+
+    /// Adds the `Item` to the set. Returns `true` if it was not present yet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, ItemSet};
+    ///
+    /// let mut set = ItemSet::with_capacity(10);
+    ///
+    /// assert!(set.insert(Item::with_id(3)));
+    /// assert!(!set.insert(Item::with_id(3)));
+    /// ```
+    ///
+    pub fn insert(&mut self, item: Item<I>) -> bool {
+        let (word, bit) = Self::position(item);
+        if word >= self.words.len() {
+            self.words.resize(word + 1, 0);
+        }
+        let present = self.words[word] & bit != 0;
+        self.words[word] |= bit;
+        !present
+    }
+
+    /// Returns `true` if the `Item` is in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, ItemSet};
+    ///
+    /// let mut set = ItemSet::with_capacity(10);
+    /// set.insert(Item::with_id(3));
+    ///
+    /// assert!(set.contains(Item::with_id(3)));
+    /// assert!(!set.contains(Item::with_id(4)));
+    /// assert!(!set.contains(Item::with_id(1000)));
+    /// ```
+    ///
+    pub fn contains(&self, item: Item<I>) -> bool {
+        let (word, bit) = Self::position(item);
+        self.words.get(word).is_some_and(|word| word & bit != 0)
+    }
+
+    /// Returns a new set holding the `Item`s of both sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, ItemSet};
+    ///
+    /// let a: ItemSet = [Item::with_id(1), Item::with_id(2)].into_iter().collect();
+    /// let b: ItemSet = [Item::with_id(2), Item::with_id(70)].into_iter().collect();
+    ///
+    /// let ids: Vec<u32> = a.union(&b).iter().map(|item| item.id()).collect();
+    /// assert_eq!(ids, vec![1, 2, 70]);
+    /// ```
+    ///
+    pub fn union(&self, other: &ItemSet<I>) -> ItemSet<I> {
+        let (longer, shorter) = if self.words.len() >= other.words.len() {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        let mut words = longer.words.clone();
+        for (word, other) in words.iter_mut().zip(&shorter.words) {
+            *word |= other;
+        }
+        ItemSet {
+            words,
+            id: PhantomData,
+        }
+    }
+
+    /// Returns a new set holding the `Item`s that are in both sets.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, ItemSet};
+    ///
+    /// let a: ItemSet = [Item::with_id(1), Item::with_id(2)].into_iter().collect();
+    /// let b: ItemSet = [Item::with_id(2), Item::with_id(70)].into_iter().collect();
+    ///
+    /// let ids: Vec<u32> = a.intersect(&b).iter().map(|item| item.id()).collect();
+    /// assert_eq!(ids, vec![2]);
+    /// ```
+    ///
+    pub fn intersect(&self, other: &ItemSet<I>) -> ItemSet<I> {
+        ItemSet {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(word, other)| word & other)
+                .collect(),
+            id: PhantomData,
+        }
+    }
+
+    /// Returns the number of `Item`s in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, ItemSet};
+    ///
+    /// let set: ItemSet = [Item::with_id(1), Item::with_id(2)].into_iter().collect();
+    /// assert_eq!(set.len(), 2);
+    /// ```
+    ///
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns `true` if the set contains no `Item`s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::ItemSet;
+    ///
+    /// let set: ItemSet = ItemSet::with_capacity(10);
+    /// assert!(set.is_empty());
+    /// ```
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Returns an iterator over the `Item`s in the set, in id order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::{Item, ItemSet};
+    ///
+    /// let set: ItemSet = [Item::with_id(5), Item::with_id(1)].into_iter().collect();
+    ///
+    /// let mut iter = set.iter();
+    /// assert_eq!(iter.next(), Some(Item::with_id(1)));
+    /// assert_eq!(iter.next(), Some(Item::with_id(5)));
+    /// assert_eq!(iter.next(), None);
+    /// ```
+    ///
+    pub fn iter(&self) -> impl Iterator<Item = Item<I>> + '_ {
+        self.words.iter().enumerate().flat_map(|(index, word)| {
+            let mut word = *word;
+            core::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(Item::at_index(index * BITS + bit))
+            })
+        })
+    }
+
+    fn position(item: Item<I>) -> (usize, u64) {
+        let index = item.as_index();
+        (index / BITS, 1 << (index % BITS))
+    }
+}
+
+impl<I: ItemId> Default for ItemSet<I> {
+    fn default() -> Self {
+        ItemSet::with_capacity(0)
+    }
+}
+
+impl<I: ItemId> FromIterator<Item<I>> for ItemSet<I> {
+    fn from_iter<It: IntoIterator<Item = Item<I>>>(iter: It) -> Self {
+        let mut set = ItemSet::default();
+        for item in iter {
+            set.insert(item);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_of(ids: &[u32]) -> ItemSet {
+        ids.iter().map(|id| Item::with_id(*id)).collect()
+    }
+
+    fn ids(set: &ItemSet) -> Vec<u32> {
+        set.iter().map(|item| item.id()).collect()
+    }
+
+    #[test]
+    fn test_membership() {
+        let mut set = ItemSet::with_capacity(10);
+        for id in [0, 3, 9, 63, 64, 200] {
+            assert!(set.insert(Item::with_id(id)));
+        }
+        assert!(!set.insert(Item::with_id(64)));
+
+        for id in 0..300 {
+            let expected = [0, 3, 9, 63, 64, 200].contains(&id);
+            assert_eq!(set.contains(Item::with_id(id)), expected);
+        }
+        assert_eq!(set.len(), 6);
+        assert_eq!(ids(&set), vec![0, 3, 9, 63, 64, 200]);
+    }
+
+    #[test]
+    fn test_union() {
+        let a = set_of(&[1, 5, 64]);
+        let b = set_of(&[5, 6, 130]);
+
+        assert_eq!(ids(&a.union(&b)), vec![1, 5, 6, 64, 130]);
+        assert_eq!(ids(&b.union(&a)), vec![1, 5, 6, 64, 130]);
+        assert_eq!(ids(&a.union(&ItemSet::default())), vec![1, 5, 64]);
+    }
+
+    #[test]
+    fn test_intersect() {
+        let a = set_of(&[1, 5, 64, 65]);
+        let b = set_of(&[5, 6, 65, 130]);
+
+        assert_eq!(ids(&a.intersect(&b)), vec![5, 65]);
+        assert_eq!(ids(&b.intersect(&a)), vec![5, 65]);
+        assert!(a.intersect(&ItemSet::default()).is_empty());
+    }
+
+    #[test]
+    fn test_other_id_type() {
+        let mut set: ItemSet<u16> = ItemSet::with_capacity(4);
+        set.insert(Item::from_index(70).unwrap());
+
+        assert!(set.contains(Item::from_index(70).unwrap()));
+        assert_eq!(
+            set.iter().map(|item| item.as_index()).collect::<Vec<_>>(),
+            vec![70]
+        );
+    }
+}
@@ -0,0 +1,200 @@
+//!
+//! A read-only form of an `Itemizer` without a hash map.
+//!
+//! `SortedItemizer` looks items up by binary search in a vector of keys
+//! sorted by value, instead of in a hash map. Lookups take O(log n) instead
+//! of O(1) time, but the keys are stored contiguously, which takes less
+//! memory and can be more cache-friendly for small vocabularies.
+//!
+//! # Examples
+//!
+//! ```
+//! use itemizer::Itemizer;
+//!
+//! let mut itemizer = Itemizer::new();
+//! let item1 = itemizer.id_of(&"item1".to_string());
+//!
+//! let sorted = itemizer.freeze_sorted();
+//! assert_eq!(sorted.id_of_opt("item1"), Some(item1));
+//! ```
+//!
+
+use crate::item::{Item, ItemId};
+use crate::itemizer::Normalizer;
+use alloc::borrow::{Borrow, ToOwned};
+use alloc::vec::Vec;
+
+/// A read-only `Itemizer` that looks items up by binary search, created by
+/// `Itemizer::freeze_sorted`.
+pub struct SortedItemizer<T, I = u32> {
+    sorted_keys: Vec<(T, Item<I>)>,
+    item_id_to_str: Vec<Option<T>>,
+    normalizer: Option<Normalizer<T>>,
+}
+
+impl<T, I> SortedItemizer<T, I>
+where
+    T: Ord,
+    I: ItemId,
+{
+    pub(crate) fn new(
+        mut sorted_keys: Vec<(T, Item<I>)>,
+        item_id_to_str: Vec<Option<T>>,
+        normalizer: Option<Normalizer<T>>,
+    ) -> SortedItemizer<T, I> {
+        sorted_keys.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+        SortedItemizer {
+            sorted_keys,
+            item_id_to_str,
+            normalizer,
+        }
+    }
+
+    /// Returns the `Item` for the given item if it exists in the
+    /// `SortedItemizer`. If the item is not present, `None` is returned.
+    ///
+    /// Like `Itemizer::id_of_opt`, this accepts any borrowed form of `T`,
+    /// but takes O(log n) time.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// let sorted = itemizer.freeze_sorted();
+    ///
+    /// assert_eq!(sorted.id_of_opt(&"item1".to_string()), Some(item1));
+    /// assert_eq!(sorted.id_of_opt("item2"), None);
+    /// ```
+    ///
+    pub fn id_of_opt<Q>(&self, item: &Q) -> Option<Item<I>>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ToOwned<Owned = T> + ?Sized,
+    {
+        match &self.normalizer {
+            Some(normalizer) => self.search::<Q>(normalizer(&item.to_owned()).borrow()),
+            None => self.search(item),
+        }
+    }
+
+    fn search<Q>(&self, key: &Q) -> Option<Item<I>>
+    where
+        T: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.sorted_keys
+            .binary_search_by(|(other, _)| other.borrow().cmp(key))
+            .ok()
+            .map(|index| self.sorted_keys[index].1)
+    }
+
+    /// Returns the value of the given `Item`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the `Item` is out of range or its id has been freed by
+    /// `Itemizer::remove` before freezing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// let item1 = itemizer.id_of(&"item1".to_string());
+    /// let sorted = itemizer.freeze_sorted();
+    ///
+    /// assert_eq!(sorted.value_of(&item1), &"item1".to_string());
+    /// ```
+    ///
+    pub fn value_of(&self, id: &Item<I>) -> &T {
+        match &self.item_id_to_str[id.as_index()] {
+            Some(value) => value,
+            None => panic!(
+                "item id {} has been removed from the itemizer",
+                id.as_index()
+            ),
+        }
+    }
+
+    /// Returns the number of items in the `SortedItemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.id_of(&"item1".to_string());
+    /// let sorted = itemizer.freeze_sorted();
+    ///
+    /// assert_eq!(sorted.len(), 1);
+    /// ```
+    ///
+    pub fn len(&self) -> usize {
+        self.item_id_to_str.len()
+    }
+
+    /// Returns `true` if the `SortedItemizer` contains no items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let itemizer: Itemizer<String> = Itemizer::new();
+    /// assert!(itemizer.freeze_sorted().is_empty());
+    /// ```
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.item_id_to_str.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::itemizer::Itemizer;
+    use alloc::format;
+    use alloc::string::{String, ToString};
+
+    fn assert_send_sync<S: Send + Sync>() {}
+
+    #[test]
+    fn test_sorted_is_sync() {
+        assert_send_sync::<SortedItemizer<String>>();
+    }
+
+    #[test]
+    fn test_freeze_sorted() {
+        let mut itemizer = Itemizer::new();
+        for i in 0..500 {
+            itemizer.id_of(&format!("item{}", (i * 7919) % 300));
+        }
+        itemizer.remove(&"item42".to_string());
+
+        let sorted = itemizer.clone().freeze_sorted();
+        assert_eq!(sorted.len(), itemizer.len());
+        for i in 0..310 {
+            let value = format!("item{}", i);
+            assert_eq!(sorted.id_of_opt(&value), itemizer.id_of_opt(&value));
+            if let Some(id) = sorted.id_of_opt(&value) {
+                assert_eq!(sorted.value_of(&id), &value);
+            }
+        }
+        assert_eq!(sorted.id_of_opt("item42"), None);
+    }
+
+    #[test]
+    fn test_freeze_sorted_case_insensitive() {
+        let mut itemizer = Itemizer::new_case_insensitive();
+        let milk = itemizer.id_of(&"Milk".to_string());
+
+        let sorted = itemizer.freeze_sorted();
+        assert_eq!(sorted.id_of_opt("MILK"), Some(milk));
+        assert_eq!(sorted.value_of(&milk), "Milk");
+    }
+}
@@ -0,0 +1,189 @@
+//!
+//! An itemizer for strings that stores every string only once.
+//!
+//! An `Itemizer<String>` keeps two copies of every string, one as the key of
+//! its map and one as the value of its `Item`. `StrItemizer` shares a single
+//! `Rc<str>` between both, which halves the memory used by the strings of
+//! large vocabularies. Because of the `Rc`, it cannot be sent to other
+//! threads.
+//!
+//! # Examples
+//!
+//! ```
+//! use itemizer::StrItemizer;
+//!
+//! let mut itemizer = StrItemizer::new();
+//! let item1 = itemizer.id_of("item1");
+//!
+//! assert_eq!(itemizer.value_of(&item1), "item1");
+//! ```
+//!
+
+use crate::error::ItemizerError;
+use crate::item::Item;
+use crate::HashMap;
+use alloc::rc::Rc;
+use alloc::vec::Vec;
+use fnv::FnvBuildHasher;
+
+/// Maps strings to `Item`s, storing every string only once.
+pub struct StrItemizer {
+    item_str_to_id: HashMap<Rc<str>, Item, FnvBuildHasher>,
+    item_id_to_str: Vec<Rc<str>>,
+}
+
+impl StrItemizer {
+    /// Creates a new, empty `StrItemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::StrItemizer;
+    ///
+    /// let itemizer = StrItemizer::new();
+    /// assert!(itemizer.is_empty());
+    /// ```
+    ///
+    pub fn new() -> StrItemizer {
+        StrItemizer {
+            item_str_to_id: HashMap::default(),
+            item_id_to_str: Vec::new(),
+        }
+    }
+
+    /// Returns the `Item` for the given string. If the string is not in the
+    /// `StrItemizer`, it is added and a new `Item` is returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if all `u32` ids have been assigned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::StrItemizer;
+    ///
+    /// let mut itemizer = StrItemizer::new();
+    /// let item1 = itemizer.id_of("item1");
+    ///
+    /// assert_eq!(itemizer.id_of("item1"), item1);
+    /// assert_eq!(itemizer.len(), 1);
+    /// ```
+    ///
+    pub fn id_of(&mut self, item: &str) -> Item {
+        if let Some(id) = self.item_str_to_id.get(item) {
+            return *id;
+        }
+
+        let id = match u32::try_from(self.item_id_to_str.len()) {
+            Ok(id) if id < u32::MAX => Item::with_id(id),
+            _ => panic!("{}", ItemizerError::IdSpaceExhausted),
+        };
+        let value: Rc<str> = Rc::from(item);
+        self.item_str_to_id.insert(Rc::clone(&value), id);
+        self.item_id_to_str.push(value);
+        id
+    }
+
+    /// Returns the `Item` for the given string if it exists in the
+    /// `StrItemizer`. If the string is not present, `None` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::StrItemizer;
+    ///
+    /// let mut itemizer = StrItemizer::new();
+    /// let item1 = itemizer.id_of("item1");
+    ///
+    /// assert_eq!(itemizer.id_of_opt("item1"), Some(item1));
+    /// assert_eq!(itemizer.id_of_opt("item2"), None);
+    /// ```
+    ///
+    pub fn id_of_opt(&self, item: &str) -> Option<Item> {
+        self.item_str_to_id.get(item).copied()
+    }
+
+    /// Returns the string of the given `Item`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::StrItemizer;
+    ///
+    /// let mut itemizer = StrItemizer::new();
+    /// let item1 = itemizer.id_of("item1");
+    ///
+    /// assert_eq!(itemizer.value_of(&item1), "item1");
+    /// ```
+    ///
+    pub fn value_of(&self, id: &Item) -> &str {
+        &self.item_id_to_str[id.as_index()]
+    }
+
+    /// Returns the number of strings in the `StrItemizer`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::StrItemizer;
+    ///
+    /// let mut itemizer = StrItemizer::new();
+    /// itemizer.id_of("item1");
+    ///
+    /// assert_eq!(itemizer.len(), 1);
+    /// ```
+    ///
+    pub fn len(&self) -> usize {
+        self.item_id_to_str.len()
+    }
+
+    /// Returns `true` if the `StrItemizer` contains no strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::StrItemizer;
+    ///
+    /// let mut itemizer = StrItemizer::new();
+    /// assert!(itemizer.is_empty());
+    ///
+    /// itemizer.id_of("item1");
+    /// assert!(!itemizer.is_empty());
+    /// ```
+    ///
+    pub fn is_empty(&self) -> bool {
+        self.item_id_to_str.is_empty()
+    }
+}
+
+impl Default for StrItemizer {
+    fn default() -> Self {
+        StrItemizer::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn test_shared_storage() {
+        let mut itemizer = StrItemizer::new();
+        let items: Vec<Item> = (0..1000)
+            .map(|i| itemizer.id_of(&format!("item{}", i % 400)))
+            .collect();
+
+        assert_eq!(itemizer.len(), 400);
+        for (i, item) in items.iter().enumerate() {
+            assert_eq!(itemizer.value_of(item), format!("item{}", i % 400));
+            assert_eq!(itemizer.id_of_opt(&format!("item{}", i % 400)), Some(*item));
+        }
+
+        let value = &itemizer.item_id_to_str[items[7].as_index()];
+        assert_eq!(Rc::strong_count(value), 2);
+        let (key, _) = itemizer.item_str_to_id.get_key_value("item7").unwrap();
+        assert!(Rc::ptr_eq(key, value));
+    }
+}
@@ -0,0 +1,143 @@
+//!
+//! Reading and writing an `Itemizer` as a table of `id\tvalue` lines.
+//!
+
+use crate::error::ItemizerError;
+use crate::item::ItemId;
+use crate::itemizer::Itemizer;
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+
+impl<T, I, S> Itemizer<T, I, S>
+where
+    T: Display,
+    I: ItemId,
+{
+    /// Writes one `id\tvalue` line per item to `w`, in id order. Ids freed by
+    /// `remove` are skipped.
+    ///
+    /// Values are written with their `Display` implementation, so values
+    /// containing tabs or line breaks cannot be told apart when reading the
+    /// table back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let mut itemizer = Itemizer::new();
+    /// itemizer.ids_of(&["milk", "bread"]);
+    ///
+    /// let mut table = vec![];
+    /// itemizer.write_table(&mut table).unwrap();
+    ///
+    /// assert_eq!(table, b"0\tmilk\n1\tbread\n");
+    /// ```
+    ///
+    pub fn write_table<W: Write>(&self, w: &mut W) -> io::Result<()> {
+        for (index, value) in self.slots().enumerate() {
+            if let Some(value) = value {
+                writeln!(w, "{}\t{}", index, value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Itemizer<String> {
+    /// Reads an `Itemizer` from a table of `id\tvalue` lines, as written by
+    /// `write_table`.
+    ///
+    /// The ids must be dense and ascending from 0, and every value must be
+    /// unique. Tables written from an `Itemizer` with removed items have gaps
+    /// and are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    ///
+    /// let itemizer = Itemizer::read_table("0\tmilk\n1\tbread\n".as_bytes()).unwrap();
+    ///
+    /// assert_eq!(itemizer.id_of_opt(&"bread".to_string()).unwrap().as_index(), 1);
+    /// ```
+    ///
+    pub fn read_table<R: BufRead>(reader: R) -> Result<Itemizer<String>, ItemizerError> {
+        let mut itemizer = Itemizer::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            let malformed = || ItemizerError::MalformedLine { line: index + 1 };
+
+            let (id, value) = line.split_once('\t').ok_or_else(malformed)?;
+            let id: usize = id.parse().map_err(|_| malformed())?;
+            if id != index {
+                return Err(ItemizerError::UnexpectedId {
+                    expected: index,
+                    found: id,
+                });
+            }
+
+            let value = value.to_string();
+            if itemizer.contains(&value) {
+                return Err(ItemizerError::DuplicateValue { id });
+            }
+            itemizer.try_id_of(&value)?;
+        }
+        Ok(itemizer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_table() {
+        let mut itemizer: Itemizer<i32> = Itemizer::new();
+        itemizer.ids_of(&[10, 20, 30, 20]);
+
+        let mut table = vec![];
+        itemizer.write_table(&mut table).unwrap();
+        assert_eq!(table, b"0\t10\n1\t20\n2\t30\n");
+
+        itemizer.remove(&20);
+        let mut table = vec![];
+        itemizer.write_table(&mut table).unwrap();
+        assert_eq!(table, b"0\t10\n2\t30\n");
+    }
+
+    #[test]
+    fn test_read_table_round_trip() {
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+        itemizer.ids_of(&["milk".to_string(), "bread".to_string(), "a b".to_string()]);
+
+        let mut table = vec![];
+        itemizer.write_table(&mut table).unwrap();
+        let restored = Itemizer::read_table(table.as_slice()).unwrap();
+
+        assert_eq!(restored, itemizer);
+    }
+
+    #[test]
+    fn test_read_table_errors() {
+        assert!(matches!(
+            Itemizer::read_table("0\ta\n2\tb\n".as_bytes()),
+            Err(ItemizerError::UnexpectedId {
+                expected: 1,
+                found: 2
+            })
+        ));
+        assert!(matches!(
+            Itemizer::read_table("0\ta\n1\ta\n".as_bytes()),
+            Err(ItemizerError::DuplicateValue { id: 1 })
+        ));
+        assert!(matches!(
+            Itemizer::read_table("0\ta\nb\n".as_bytes()),
+            Err(ItemizerError::MalformedLine { line: 2 })
+        ));
+        assert!(matches!(
+            Itemizer::read_table("x\ta\n".as_bytes()),
+            Err(ItemizerError::MalformedLine { line: 1 })
+        ));
+    }
+}
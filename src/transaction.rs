@@ -0,0 +1,158 @@
+//!
+//! Encoding of transactions, i.e. lines of separated values, into `Item`s.
+//!
+
+use crate::item::{Item, ItemId};
+use crate::itemizer::Itemizer;
+use fnv::FnvBuildHasher;
+use std::hash::{BuildHasher, Hash};
+use std::io::{self, BufRead};
+
+impl<T, I, S> Itemizer<T, I, S>
+where
+    T: Eq + Hash + Clone + for<'a> From<&'a str>,
+    I: ItemId,
+    S: BuildHasher,
+{
+    /// Reads one transaction per line from `reader`, splits it on `sep` and
+    /// returns the `Item`s of each transaction. Empty values, e.g. from
+    /// repeated separators, are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    /// use std::io::Cursor;
+    ///
+    /// let mut itemizer: Itemizer<String> = Itemizer::new();
+    /// let transactions = itemizer
+    ///     .itemize_transactions(Cursor::new("milk bread\nbread eggs\n"), ' ')
+    ///     .unwrap();
+    ///
+    /// assert_eq!(transactions.len(), 2);
+    /// assert_eq!(transactions[0][1], transactions[1][0]);
+    /// assert_eq!(itemizer.len(), 3);
+    /// ```
+    ///
+    pub fn itemize_transactions<R: BufRead>(
+        &mut self,
+        reader: R,
+        sep: char,
+    ) -> io::Result<Vec<Vec<Item<I>>>> {
+        reader
+            .lines()
+            .map(|line| Ok(self.itemize_line(&line?, sep)))
+            .collect()
+    }
+
+    /// Returns a `TransactionEncoder` that lazily reads one transaction per
+    /// line from `reader` and encodes it like `itemize_transactions`, so that
+    /// the encoded transactions never have to be held in memory at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use itemizer::Itemizer;
+    /// use std::io::Cursor;
+    ///
+    /// let mut itemizer: Itemizer<String> = Itemizer::new();
+    /// for transaction in itemizer.encode_lines(Cursor::new("milk bread\nbread eggs\n"), ' ') {
+    ///     assert_eq!(transaction.unwrap().len(), 2);
+    /// }
+    ///
+    /// assert_eq!(itemizer.len(), 3);
+    /// ```
+    ///
+    pub fn encode_lines<R: BufRead>(
+        &mut self,
+        reader: R,
+        sep: char,
+    ) -> TransactionEncoder<'_, R, T, I, S> {
+        TransactionEncoder {
+            itemizer: self,
+            lines: reader.lines(),
+            sep,
+        }
+    }
+
+    fn itemize_line(&mut self, line: &str, sep: char) -> Vec<Item<I>> {
+        line.split(sep)
+            .filter(|token| !token.is_empty())
+            .map(|token| self.id_of_owned(T::from(token)))
+            .collect()
+    }
+}
+
+/// An iterator that reads and encodes one transaction per line.
+///
+/// This is created by the `encode_lines` method on `Itemizer`.
+pub struct TransactionEncoder<'a, R, T, I = u32, S = FnvBuildHasher> {
+    itemizer: &'a mut Itemizer<T, I, S>,
+    lines: io::Lines<R>,
+    sep: char,
+}
+
+impl<R, T, I, S> Iterator for TransactionEncoder<'_, R, T, I, S>
+where
+    R: BufRead,
+    T: Eq + Hash + Clone + for<'b> From<&'b str>,
+    I: ItemId,
+    S: BuildHasher,
+{
+    type Item = io::Result<Vec<Item<I>>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let line = self.lines.next()?;
+        Some(line.map(|line| self.itemizer.itemize_line(&line, self.sep)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_itemize_transactions() {
+        let input = "milk bread butter\nbread  eggs\n\nmilk eggs";
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+
+        let transactions = itemizer
+            .itemize_transactions(Cursor::new(input), ' ')
+            .unwrap();
+
+        let ids = |ids: &[u32]| ids.iter().map(|&id| Item::with_id(id)).collect::<Vec<_>>();
+        assert_eq!(
+            transactions,
+            vec![ids(&[0, 1, 2]), ids(&[1, 3]), ids(&[]), ids(&[0, 3])]
+        );
+        assert_eq!(itemizer.value_of(&Item::with_id(3)), "eggs");
+    }
+
+    #[test]
+    fn test_itemize_transactions_separator() {
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+
+        let transactions = itemizer
+            .itemize_transactions(Cursor::new("a,b c\nb c,a\n"), ',')
+            .unwrap();
+
+        assert_eq!(transactions[0], vec![Item::with_id(0), Item::with_id(1)]);
+        assert_eq!(transactions[1], vec![Item::with_id(1), Item::with_id(0)]);
+    }
+
+    #[test]
+    fn test_encode_lines() {
+        let input = "milk bread butter\nbread  eggs\n\nmilk eggs";
+        let mut itemizer: Itemizer<String> = Itemizer::new();
+
+        let mut encoder = itemizer.encode_lines(Cursor::new(input), ' ');
+        let first = encoder.next().unwrap().unwrap();
+        assert_eq!(first.len(), 3);
+
+        let rest: Vec<Vec<Item>> = encoder.collect::<io::Result<_>>().unwrap();
+        let ids = |ids: &[u32]| ids.iter().map(|&id| Item::with_id(id)).collect::<Vec<_>>();
+        assert_eq!(rest, vec![ids(&[1, 3]), ids(&[]), ids(&[0, 3])]);
+        assert_eq!(itemizer.len(), 4);
+    }
+}